@@ -5,6 +5,7 @@ use serde::Deserialize;
 use crate::Jenkins;
 use crate::client::{self, Result};
 use crate::client_internals::path::Path;
+use crate::fingerprint::Fingerprint;
 
 /// Artifact produced by a build
 #[derive(Deserialize, Debug)]
@@ -28,6 +29,12 @@ pub struct Artifact {
     /// Version
     pub version: String,
 }
+impl Artifact {
+    /// Get the `Fingerprint` of this artifact, to know which builds used it
+    pub async fn get_fingerprint(&self, jenkins_client: &Jenkins) -> Result<Fingerprint> {
+        jenkins_client.get_fingerprint(&self.md5sum).await
+    }
+}
 
 /// Short Maven Artifact Record that is returned when getting a maven build
 #[derive(Deserialize, Debug)]
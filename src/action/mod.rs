@@ -110,6 +110,24 @@ pub struct FlowGraphAction {
 }
 register_class!("org.jenkinsci.plugins.workflow.job.views.FlowGraphAction" => FlowGraphAction);
 impl Action for FlowGraphAction {}
+impl FlowGraphAction {
+    /// Rebuild the tree of steps of the pipeline. Jenkins only provides the IDs and parents of
+    /// the nodes from a depth of 2, and their timing from a depth of 3. Below a depth of 2, the
+    /// graph is empty
+    pub fn graph(&self) -> pipeline::PipelineGraph {
+        pipeline::PipelineGraph::from_nodes(&self.nodes)
+    }
+}
+
+/// An action on a node of a pipeline, with the time it started
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingAction {
+    /// When the node started, in milliseconds since epoch
+    pub start_time: u64,
+}
+register_class!("org.jenkinsci.plugins.workflow.actions.TimingAction" => TimingAction);
+impl Action for TimingAction {}
 
 /// An action with maven artifacts
 #[derive(Deserialize, Debug)]
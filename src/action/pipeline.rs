@@ -1,11 +1,21 @@
 //! Nodes found in a pipeline
 
+use std::collections::HashMap;
+use std::fmt::Write;
+
 use serde::{self, Deserialize, Serialize};
 
+use super::{CommonAction, TimingAction};
 use crate::helpers::Class;
+use crate::job::BallColor;
 
 /// Trait implemented by specialization of PipelineNode
-pub trait PipelineNode {}
+pub trait PipelineNode {
+    /// ID of the node
+    fn id(&self) -> &str;
+    /// IDs of the nodes preceding this one
+    fn parents(&self) -> &[String];
+}
 
 /// A node of a pipeline
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,6 +24,22 @@ pub struct CommonPipelineNode {
     /// _class provided by Jenkins
     #[serde(rename = "_class")]
     pub class: Option<String>,
+    /// ID of the node. Empty if Jenkins only provided the class, depending on the depth
+    #[serde(default)]
+    pub id: String,
+    /// Display name of the node, its label for a block with a label
+    #[serde(default)]
+    pub display_name: String,
+    /// Name of the function of the step, like `sh` or `stage`
+    pub display_function_name: Option<String>,
+    /// IDs of the nodes preceding this one
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// Ball Color for the status of the node
+    pub icon_color: Option<BallColor>,
+    /// Actions of the node
+    #[serde(default)]
+    pub actions: Vec<CommonAction>,
 
     #[cfg(not(feature = "extra-fields-visibility"))]
     #[serde(flatten)]
@@ -24,39 +50,446 @@ pub struct CommonPipelineNode {
     pub extra_fields: serde_json::Value,
 }
 specialize!(CommonPipelineNode => PipelineNode);
-impl PipelineNode for CommonPipelineNode {}
+impl PipelineNode for CommonPipelineNode {
+    fn id(&self) -> &str {
+        &self.id
+    }
 
-/// Beginning of a flow
-#[derive(Deserialize, Debug, Copy, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct FlowStartNode {}
+    fn parents(&self) -> &[String] {
+        &self.parents
+    }
+}
+impl CommonPipelineNode {
+    /// When the node started, in milliseconds since epoch, if Jenkins provides it
+    pub fn start_time(&self) -> Option<u64> {
+        self.actions
+            .iter()
+            .find_map(|action| action.as_variant::<TimingAction>().ok())
+            .map(|timing| timing.start_time)
+    }
+
+    fn is<T: Class>(&self) -> bool {
+        self.class.as_deref() == Some(T::with_class())
+    }
+}
+
+macro_rules! pipeline_node_with_common_fields_and_impl {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {}
+    ) => {
+        $(#[$attr])*
+        pub struct $name {
+            /// ID of the node. Empty if Jenkins only provided the class, depending on the depth
+            #[serde(default)]
+            pub id: String,
+            /// Display name of the node
+            #[serde(default)]
+            pub display_name: String,
+            /// Name of the function of the step
+            pub display_function_name: Option<String>,
+            /// IDs of the nodes preceding this one
+            #[serde(default)]
+            pub parents: Vec<String>,
+            /// Ball Color for the status of the node
+            pub icon_color: Option<BallColor>,
+        }
+        impl PipelineNode for $name {
+            fn id(&self) -> &str {
+                &self.id
+            }
+
+            fn parents(&self) -> &[String] {
+                &self.parents
+            }
+        }
+    };
+}
+
+pipeline_node_with_common_fields_and_impl!(
+    /// Beginning of a flow
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FlowStartNode {}
+);
 register_class!("org.jenkinsci.plugins.workflow.graph.FlowStartNode" => FlowStartNode);
-impl PipelineNode for FlowStartNode {}
 
-/// Beginning of a step
-#[derive(Deserialize, Debug, Copy, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct StepStartNode {}
+pipeline_node_with_common_fields_and_impl!(
+    /// Beginning of a step
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepStartNode {}
+);
 register_class!("org.jenkinsci.plugins.workflow.cps.nodes.StepStartNode" => StepStartNode);
-impl PipelineNode for StepStartNode {}
 
-/// A step
-#[derive(Deserialize, Debug, Copy, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct StepAtomNode {}
+pipeline_node_with_common_fields_and_impl!(
+    /// A step
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepAtomNode {}
+);
 register_class!("org.jenkinsci.plugins.workflow.cps.nodes.StepAtomNode" => StepAtomNode);
-impl PipelineNode for StepAtomNode {}
 
-/// End of a step
-#[derive(Deserialize, Debug, Copy, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct StepEndNode {}
+pipeline_node_with_common_fields_and_impl!(
+    /// End of a step
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepEndNode {}
+);
 register_class!("org.jenkinsci.plugins.workflow.cps.nodes.StepEndNode" => StepEndNode);
-impl PipelineNode for StepEndNode {}
 
-/// End of a flow
-#[derive(Deserialize, Debug, Copy, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct FlowEndNode {}
+pipeline_node_with_common_fields_and_impl!(
+    /// End of a flow
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FlowEndNode {}
+);
 register_class!("org.jenkinsci.plugins.workflow.graph.FlowEndNode" => FlowEndNode);
-impl PipelineNode for FlowEndNode {}
+
+/// Kind of node in a `PipelineGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphNodeKind {
+    /// The whole pipeline
+    Flow,
+    /// A block, like a stage, a parallel branch or a `node`
+    Block,
+    /// A step without a body, like `sh`
+    Step,
+}
+
+/// A node of a `PipelineGraph`
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    /// ID of the pipeline node starting this node
+    pub id: String,
+    /// Display name of the node
+    pub name: String,
+    /// Name of the function of the step
+    pub function: Option<String>,
+    /// Kind of node
+    pub kind: GraphNodeKind,
+    /// Ball Color for the status of the node
+    pub status: Option<BallColor>,
+    /// When the node started, in milliseconds since epoch
+    pub start_time: Option<u64>,
+    /// When the node ended, in milliseconds since epoch. Not set while it is running
+    pub end_time: Option<u64>,
+    /// Index of the parent node in the graph
+    pub parent: Option<usize>,
+    /// Indexes of the children nodes in the graph
+    pub children: Vec<usize>,
+}
+impl GraphNode {
+    /// Duration of the node, in milliseconds
+    pub fn duration(&self) -> Option<u64> {
+        Some(self.end_time?.saturating_sub(self.start_time?))
+    }
+
+    fn label(&self) -> String {
+        let mut label = self.name.clone();
+        if let Some(duration) = self.duration() {
+            let _ = write!(label, " ({}.{:03}s)", duration / 1000, duration % 1000);
+        }
+        if let Some(status) = self
+            .status
+            .and_then(|status| serde_json::to_value(status).ok())
+            .and_then(|status| status.as_str().map(ToString::to_string))
+        {
+            let _ = write!(label, " [{status}]");
+        }
+        label
+    }
+}
+
+/// Tree of the steps of a pipeline, with blocks and parallel branches, rebuilt from the nodes
+/// of a `FlowGraphAction`
+#[derive(Debug, Clone)]
+pub struct PipelineGraph {
+    nodes: Vec<GraphNode>,
+}
+impl PipelineGraph {
+    /// Rebuild the tree from the nodes of a pipeline. The first node is the root of the tree
+    pub fn from_nodes(nodes: &[CommonPipelineNode]) -> Self {
+        let mut sorted: Vec<&CommonPipelineNode> =
+            nodes.iter().filter(|node| !node.id.is_empty()).collect();
+        sorted.sort_by_key(|node| (node.id.parse::<u64>().unwrap_or(u64::MAX), node.id.clone()));
+
+        let mut graph = PipelineGraph { nodes: vec![] };
+        // for each pipeline node, the index of the block its successors are in
+        let mut next_container: HashMap<&str, usize> = HashMap::new();
+        // for each step, its index, to end it when the next node starts
+        let mut steps: HashMap<&str, usize> = HashMap::new();
+
+        for node in sorted {
+            let start_time = node.start_time();
+            for parent in &node.parents {
+                if let Some(&step) = steps.get(parent.as_str()) {
+                    graph.nodes[step].end_time = start_time;
+                }
+            }
+            let container = node
+                .parents
+                .first()
+                .and_then(|parent| next_container.get(parent.as_str()).copied());
+
+            if node.is::<StepEndNode>() || node.is::<FlowEndNode>() {
+                let closed = container.unwrap_or(0);
+                if let Some(block) = graph.nodes.get_mut(closed) {
+                    block.end_time = start_time;
+                    if node.icon_color.is_some() {
+                        block.status = node.icon_color;
+                    }
+                }
+                let _ = next_container.insert(
+                    &node.id,
+                    graph
+                        .nodes
+                        .get(closed)
+                        .and_then(|block| block.parent)
+                        .unwrap_or(0),
+                );
+                continue;
+            }
+
+            let kind = if graph.nodes.is_empty() || node.is::<FlowStartNode>() {
+                GraphNodeKind::Flow
+            } else if node.is::<StepStartNode>() {
+                GraphNodeKind::Block
+            } else {
+                GraphNodeKind::Step
+            };
+            let index = graph.nodes.len();
+            let parent = if index == 0 {
+                None
+            } else {
+                Some(container.unwrap_or(0))
+            };
+            graph.nodes.push(GraphNode {
+                id: node.id.clone(),
+                name: node.display_name.clone(),
+                function: node.display_function_name.clone(),
+                kind,
+                status: node.icon_color,
+                start_time,
+                end_time: None,
+                parent,
+                children: vec![],
+            });
+            if let Some(parent) = parent {
+                graph.nodes[parent].children.push(index);
+            }
+            match kind {
+                GraphNodeKind::Flow | GraphNodeKind::Block => {
+                    let _ = next_container.insert(&node.id, index);
+                }
+                GraphNodeKind::Step => {
+                    let _ = next_container.insert(&node.id, parent.unwrap_or(0));
+                    let _ = steps.insert(&node.id, index);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Nodes of the graph. The first one is the root
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// Root of the graph, if there is any node
+    pub fn root(&self) -> Option<&GraphNode> {
+        self.nodes.first()
+    }
+
+    /// Render the graph as an indented text tree, with durations and statuses
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if !self.nodes.is_empty() {
+            self.write_text(&mut text, 0, 0);
+        }
+        text
+    }
+
+    fn write_text(&self, text: &mut String, index: usize, depth: usize) {
+        let node = &self.nodes[index];
+        let _ = writeln!(text, "{}{}", "  ".repeat(depth), node.label());
+        for &child in &node.children {
+            self.write_text(text, child, depth + 1);
+        }
+    }
+
+    /// Render the graph in the Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pipeline {\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", shape={}];",
+                node.id,
+                node.label().replace('\\', "\\\\").replace('"', "\\\""),
+                match node.kind {
+                    GraphNodeKind::Flow | GraphNodeKind::Block => "box",
+                    GraphNodeKind::Step => "ellipse",
+                }
+            );
+        }
+        for node in &self.nodes {
+            for &child in &node.children {
+                let _ = writeln!(dot, "  \"{}\" -> \"{}\";", node.id, self.nodes[child].id);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(class: &str, id: u32, name: &str, parents: &[u32], start: u64) -> serde_json::Value {
+        serde_json::json!({
+            "_class": format!("org.jenkinsci.plugins.workflow.{class}"),
+            "id": id.to_string(),
+            "displayName": name,
+            "parents": parents.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "iconColor": "blue",
+            "actions": [{
+                "_class": "org.jenkinsci.plugins.workflow.actions.TimingAction",
+                "startTime": start,
+            }],
+        })
+    }
+
+    #[test]
+    fn can_rebuild_parallel_branches() {
+        let nodes: Vec<CommonPipelineNode> = serde_json::from_value(serde_json::json!([
+            node("graph.FlowStartNode", 2, "Start of Pipeline", &[], 0),
+            node("cps.nodes.StepStartNode", 3, "parallel", &[2], 100),
+            node("cps.nodes.StepStartNode", 4, "Branch: a", &[3], 200),
+            node("cps.nodes.StepStartNode", 5, "Branch: b", &[3], 200),
+            node("cps.nodes.StepAtomNode", 6, "sh", &[4], 300),
+            node("cps.nodes.StepAtomNode", 7, "sh", &[5], 300),
+            node("cps.nodes.StepEndNode", 8, "parallel", &[6], 1300),
+            node("cps.nodes.StepEndNode", 9, "parallel", &[7], 2300),
+            node("cps.nodes.StepEndNode", 10, "parallel", &[8, 9], 2400),
+            node("cps.nodes.StepAtomNode", 11, "echo", &[10], 2500),
+            node("graph.FlowEndNode", 12, "End of Pipeline", &[11], 2600),
+        ]))
+        .unwrap();
+
+        let graph = PipelineGraph::from_nodes(&nodes);
+
+        assert_eq!(
+            graph.to_text(),
+            "Start of Pipeline (2.600s) [blue]
+  parallel (2.300s) [blue]
+    Branch: a (1.100s) [blue]
+      sh (1.000s) [blue]
+    Branch: b (2.100s) [blue]
+      sh (2.000s) [blue]
+  echo (0.100s) [blue]
+"
+        );
+        assert!(graph.to_dot().contains("\"4\" -> \"6\";"));
+    }
+
+    #[test]
+    fn can_read_node_without_fields() {
+        let node: CommonPipelineNode = serde_json::from_str(
+            r#"{"_class": "org.jenkinsci.plugins.workflow.cps.nodes.StepStartNode"}"#,
+        )
+        .unwrap();
+
+        assert!(node.as_variant::<StepStartNode>().unwrap().id.is_empty());
+        assert!(PipelineGraph::from_nodes(&[node]).root().is_none());
+    }
+
+    #[tokio::test]
+    async fn can_rebuild_stages_of_a_build() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .with_depth(3)
+            .build()
+            .unwrap();
+
+        let mut stage = node("cps.nodes.StepStartNode", 3, "Stage : Start", &[2], 100);
+        stage["displayFunctionName"] = "stage".into();
+        let _ = s
+            .mock("GET", "/job/pipeline/1/api/json")
+            .match_query(mockito::Matcher::UrlEncoded("depth".into(), "3".into()))
+            .with_body(
+                serde_json::json!({
+                    "url": format!("{}/job/pipeline/1/", s.url()),
+                    "number": 1,
+                    "duration": 1300,
+                    "estimatedDuration": 1300,
+                    "timestamp": 0,
+                    "keepLog": false,
+                    "result": "SUCCESS",
+                    "displayName": "#1",
+                    "building": false,
+                    "id": "1",
+                    "queueId": 1,
+                    "actions": [{
+                        "_class": "org.jenkinsci.plugins.workflow.job.views.FlowGraphAction",
+                        "nodes": [
+                            node("graph.FlowStartNode", 2, "Start of Pipeline", &[], 0),
+                            stage,
+                            node("cps.nodes.StepStartNode", 4, "build", &[3], 110),
+                            node("cps.nodes.StepAtomNode", 5, "sh", &[4], 200),
+                            node("cps.nodes.StepEndNode", 6, "build", &[5], 1200),
+                            node("cps.nodes.StepEndNode", 7, "Stage : Start", &[6], 1210),
+                            node("graph.FlowEndNode", 8, "End of Pipeline", &[7], 1300),
+                        ],
+                    }],
+                    "artifacts": [],
+                })
+                .to_string(),
+            )
+            .create();
+
+        let build = jenkins_client.get_build("pipeline", 1).await.unwrap();
+        let graph = build
+            .actions
+            .iter()
+            .find_map(|action| action.as_variant::<crate::action::FlowGraphAction>().ok())
+            .unwrap()
+            .graph();
+
+        let nodes = graph.nodes();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(
+            nodes.iter().map(|node| node.kind).collect::<Vec<_>>(),
+            vec![
+                GraphNodeKind::Flow,
+                GraphNodeKind::Block,
+                GraphNodeKind::Block,
+                GraphNodeKind::Step,
+            ]
+        );
+        assert_eq!(
+            nodes.iter().map(|node| node.parent).collect::<Vec<_>>(),
+            vec![None, Some(0), Some(1), Some(2)]
+        );
+        assert_eq!(nodes[1].function.as_deref(), Some("stage"));
+        assert_eq!(
+            nodes.iter().map(GraphNode::duration).collect::<Vec<_>>(),
+            vec![Some(1300), Some(1110), Some(1090), Some(1000)]
+        );
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph pipeline {
+  "2" [label="Start of Pipeline (1.300s) [blue]", shape=box];
+  "3" [label="Stage : Start (1.110s) [blue]", shape=box];
+  "4" [label="build (1.090s) [blue]", shape=box];
+  "5" [label="sh (1.000s) [blue]", shape=ellipse];
+  "2" -> "3";
+  "3" -> "4";
+  "4" -> "5";
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,620 @@
+//! Jenkins Blue Ocean REST API, from the `blueocean-rest` plugin
+//!
+//! The methods fail with an
+//! [`Error::MissingCapability`](../client/enum.Error.html#variant.MissingCapability) if the
+//! plugin is known not to be installed
+//!
+//! Blue Ocean links are relative to the root of Jenkins, so converting a pipeline or a run to
+//! its job or build in the classic API needs the client, with
+//! [`BluePipeline::to_short_job`](struct.BluePipeline.html#method.to_short_job) and
+//! [`BlueRun::to_short_build`](struct.BlueRun.html#method.to_short_build)
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::build::{ShortBuild, WorkflowRun};
+use crate::client::{self, Result};
+use crate::client_internals::{Name, Path};
+use crate::job::{ShortJob, WorkflowJob};
+use crate::version::Capability;
+
+/// Prefix of the Blue Ocean REST API for the default organization
+const BLUE_OCEAN_PREFIX: &str = "/blue/rest/organizations/jenkins";
+
+/// A page of results, as requested from Blue Ocean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Pagination {
+    /// Index of the first result
+    pub start: u32,
+    /// Maximum number of results
+    pub limit: u32,
+}
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination {
+            start: 0,
+            limit: 100,
+        }
+    }
+}
+impl Pagination {
+    /// Create a pagination with `limit` results per page, starting at the first one
+    pub fn with_limit(limit: u32) -> Self {
+        Pagination { start: 0, limit }
+    }
+
+    /// The page following this one
+    pub fn next(self) -> Self {
+        Pagination {
+            start: self.start + self.limit,
+            limit: self.limit,
+        }
+    }
+}
+
+/// A link to a Blue Ocean resource
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlueLink {
+    /// Path of the resource, starting with `/blue/rest/`
+    pub href: String,
+}
+
+/// Links of a Blue Ocean resource
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlueLinks {
+    /// Link to the resource itself
+    #[serde(rename = "self")]
+    pub self_link: BlueLink,
+}
+
+/// State of a run, node or step
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlueRunState {
+    /// Waiting in the queue
+    Queued,
+    /// Running
+    Running,
+    /// Waiting for an input
+    Paused,
+    /// Skipped
+    Skipped,
+    /// Not built
+    NotBuilt,
+    /// Finished
+    Finished,
+    /// A state unknown to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Result of a run, node or step
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlueRunResult {
+    /// Successful
+    Success,
+    /// Unstable
+    Unstable,
+    /// Failed
+    Failure,
+    /// Not built
+    NotBuilt,
+    /// Aborted
+    Aborted,
+    /// Not finished yet, or a result unknown to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Metadata of a branch of a multibranch pipeline
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueBranch {
+    /// Is this the primary branch of the repository
+    pub is_primary: bool,
+    /// URL of the branch
+    pub url: Option<String>,
+}
+
+/// Metadata of a pull request built by a multibranch pipeline
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BluePullRequest {
+    /// ID of the pull request
+    pub id: String,
+    /// Title of the pull request
+    pub title: Option<String>,
+    /// Author of the pull request
+    pub author: Option<String>,
+    /// URL of the pull request
+    pub url: Option<String>,
+}
+
+/// A pipeline, a multibranch pipeline or one of its branches
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BluePipeline {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Links to the pipeline
+    #[serde(rename = "_links")]
+    pub links: BlueLinks,
+    /// Name of the pipeline
+    pub name: String,
+    /// Full name of the pipeline, including its folders
+    pub full_name: String,
+    /// Display name of the pipeline
+    pub display_name: Option<String>,
+    /// Organization of the pipeline
+    pub organization: Option<String>,
+    /// Health of the pipeline, from 0 to 100
+    pub weather_score: Option<u32>,
+    /// Latest run of the pipeline
+    pub latest_run: Option<BlueRun>,
+    /// Branch metadata, for branches of a multibranch pipeline
+    pub branch: Option<BlueBranch>,
+    /// Pull request metadata, for pull requests of a multibranch pipeline
+    pub pull_request: Option<BluePullRequest>,
+}
+impl BluePipeline {
+    /// Get the branches and pull requests of a multibranch pipeline
+    pub async fn get_branches(
+        &self,
+        jenkins_client: &Jenkins,
+        page: Pagination,
+    ) -> Result<Vec<BluePipeline>> {
+        jenkins_client
+            .get_blue(&format!("{}branches/", self.links.self_link.href), page)
+            .await
+    }
+
+    /// Get the runs of this pipeline, most recent first
+    pub async fn get_runs(
+        &self,
+        jenkins_client: &Jenkins,
+        page: Pagination,
+    ) -> Result<Vec<BlueRun>> {
+        jenkins_client
+            .get_blue(&format!("{}runs/", self.links.self_link.href), page)
+            .await
+    }
+
+    /// Get a run of this pipeline
+    pub async fn get_run(&self, jenkins_client: &Jenkins, id: &str) -> Result<BlueRun> {
+        jenkins_client
+            .get_blue(
+                &format!(
+                    "{}runs/{}/",
+                    self.links.self_link.href,
+                    urlencoding::encode(id)
+                ),
+                (),
+            )
+            .await
+    }
+}
+
+/// A run of a pipeline
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueRun {
+    /// Links to the run
+    #[serde(rename = "_links")]
+    pub links: BlueLinks,
+    /// ID of the run, its build number
+    pub id: String,
+    /// Name of the pipeline, or of the branch for multibranch pipelines
+    pub pipeline: String,
+    /// Organization of the pipeline
+    pub organization: Option<String>,
+    /// State of the run
+    pub state: Option<BlueRunState>,
+    /// Result of the run
+    pub result: Option<BlueRunResult>,
+    /// When the run was queued
+    pub en_queue_time: Option<String>,
+    /// When the run started
+    pub start_time: Option<String>,
+    /// When the run ended
+    pub end_time: Option<String>,
+    /// Duration of the run
+    pub duration_in_millis: Option<u64>,
+    /// Estimated duration of the run
+    pub estimated_duration_in_millis: Option<i64>,
+    /// Summary of the run, like `stable` or `broken since build #2`
+    pub run_summary: Option<String>,
+}
+impl BlueRun {
+    /// Get the nodes of the pipeline graph of this run, like stages and parallel branches
+    pub async fn get_nodes(&self, jenkins_client: &Jenkins) -> Result<Vec<BluePipelineNode>> {
+        jenkins_client
+            .get_blue(&format!("{}nodes/", self.links.self_link.href), ())
+            .await
+    }
+
+    /// Get all the steps of this run
+    pub async fn get_steps(&self, jenkins_client: &Jenkins) -> Result<Vec<BluePipelineStep>> {
+        jenkins_client
+            .get_blue(&format!("{}steps/", self.links.self_link.href), ())
+            .await
+    }
+
+    /// Get the steps of a node of this run
+    pub async fn get_node_steps(
+        &self,
+        jenkins_client: &Jenkins,
+        node_id: &str,
+    ) -> Result<Vec<BluePipelineStep>> {
+        jenkins_client
+            .get_blue(
+                &format!(
+                    "{}nodes/{}/steps/",
+                    self.links.self_link.href,
+                    urlencoding::encode(node_id)
+                ),
+                (),
+            )
+            .await
+    }
+
+    /// Get the summary of the test results of this run
+    pub async fn get_test_summary(&self, jenkins_client: &Jenkins) -> Result<BlueTestSummary> {
+        jenkins_client
+            .get_blue(
+                &format!("{}blueTestSummary/", self.links.self_link.href),
+                (),
+            )
+            .await
+    }
+}
+
+/// An edge from a node of a pipeline graph to another
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueEdge {
+    /// ID of the target node
+    pub id: String,
+}
+
+/// A node of a pipeline graph, like a stage or a parallel branch
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BluePipelineNode {
+    /// Links to the node
+    #[serde(rename = "_links")]
+    pub links: BlueLinks,
+    /// ID of the node
+    pub id: String,
+    /// Display name of the node
+    pub display_name: String,
+    /// Type of node, like `STAGE` or `PARALLEL`
+    #[serde(rename = "type")]
+    pub node_type: Option<String>,
+    /// State of the node, not set if it didn't run
+    pub state: Option<BlueRunState>,
+    /// Result of the node, not set if it didn't run
+    pub result: Option<BlueRunResult>,
+    /// When the node started
+    pub start_time: Option<String>,
+    /// Duration of the node
+    pub duration_in_millis: Option<u64>,
+    /// Nodes following this one
+    #[serde(default)]
+    pub edges: Vec<BlueEdge>,
+    /// ID of the node this one starts from
+    pub first_parent: Option<String>,
+}
+
+/// A step of a pipeline, like `sh` or `echo`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BluePipelineStep {
+    /// Links to the step
+    #[serde(rename = "_links")]
+    pub links: BlueLinks,
+    /// ID of the step
+    pub id: String,
+    /// Display name of the step
+    pub display_name: String,
+    /// Description of the step, like the script of a `sh` step
+    pub display_description: Option<String>,
+    /// Type of step
+    #[serde(rename = "type")]
+    pub step_type: Option<String>,
+    /// State of the step
+    pub state: Option<BlueRunState>,
+    /// Result of the step
+    pub result: Option<BlueRunResult>,
+    /// When the step started
+    pub start_time: Option<String>,
+    /// Duration of the step
+    pub duration_in_millis: Option<u64>,
+}
+impl BluePipelineStep {
+    /// Get the log of this step
+    pub async fn get_log(&self, jenkins_client: &Jenkins) -> Result<String> {
+        jenkins_client.require(Capability::BlueOcean).await?;
+        let path = format!("{}log/", self.links.self_link.href);
+        Ok(jenkins_client
+            .get_blob(&Path::Raw { path: &path })
+            .await?
+            .text()
+            .await?)
+    }
+}
+
+/// Summary of the test results of a run
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueTestSummary {
+    /// Tests failing in this run and in the previous one
+    pub existing_failed: u32,
+    /// Tests failing in this run
+    pub failed: u32,
+    /// Tests failing in the previous run, and passing in this one
+    pub fixed: u32,
+    /// Tests passing
+    pub passed: u32,
+    /// Tests passing in the previous run, and failing in this one
+    pub regressions: u32,
+    /// Tests skipped
+    pub skipped: u32,
+    /// Total number of tests
+    pub total: u32,
+}
+
+/// Convert a Blue Ocean link to the URL of the same object in the classic API
+fn classic_url(href: &str) -> String {
+    let path = href.strip_prefix(BLUE_OCEAN_PREFIX).unwrap_or(href);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut url = String::from("/");
+    for pair in segments.chunks(2) {
+        match pair {
+            ["pipelines" | "branches", name] => url.push_str(&format!("job/{name}/")),
+            ["runs", id] => url.push_str(&format!("{id}/")),
+            _ => (),
+        }
+    }
+    url
+}
+
+impl BluePipeline {
+    /// Get the `ShortJob` of this pipeline in the classic API
+    pub fn to_short_job(&self, jenkins_client: &Jenkins) -> ShortJob<WorkflowJob> {
+        ShortJob {
+            name: self.name.clone(),
+            url: jenkins_client.url(&classic_url(&self.links.self_link.href)),
+            color: None,
+            extra_fields: None,
+            job_type: PhantomData,
+        }
+    }
+}
+
+impl BlueRun {
+    /// Get the `ShortBuild` of this run in the classic API
+    ///
+    /// # Errors
+    /// If the ID of the run is not a build number, this will return an
+    /// [`Error::InvalidUrl`](../client/enum.Error.html#variant.InvalidUrl)
+    pub fn to_short_build(&self, jenkins_client: &Jenkins) -> Result<ShortBuild<WorkflowRun>> {
+        let url = jenkins_client.url(&classic_url(&self.links.self_link.href));
+        let number = match self.id.parse() {
+            Ok(number) => number,
+            Err(_) => {
+                return Err(client::Error::InvalidUrl {
+                    url,
+                    expected: client::error::ExpectedType::Build,
+                }
+                .into());
+            }
+        };
+        Ok(ShortBuild {
+            url,
+            number,
+            display_name: None,
+            timestamp: None,
+            extra_fields: None,
+            build_type: PhantomData,
+        })
+    }
+}
+
+impl Jenkins {
+    async fn get_blue<T, Q>(&self, path: &str, qps: Q) -> Result<T>
+    where
+        for<'de> T: Deserialize<'de>,
+        Q: Serialize,
+    {
+        self.require(Capability::BlueOcean).await?;
+        Ok(self
+            .get_blob_with_params(&Path::Raw { path }, qps)
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// Get the pipelines of Blue Ocean, including folders
+    pub async fn get_blue_pipelines(&self, page: Pagination) -> Result<Vec<BluePipeline>> {
+        self.require(Capability::BlueOcean).await?;
+        Ok(self
+            .get_blob_with_params(&Path::BluePipelines, page)
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// Get a pipeline from Blue Ocean, by its full name (`folder/pipeline`)
+    pub async fn get_blue_pipeline(&self, full_name: &str) -> Result<BluePipeline> {
+        self.require(Capability::BlueOcean).await?;
+        Ok(self
+            .get_blob(&Path::BluePipeline {
+                full_name: Name::Name(full_name),
+            })
+            .await?
+            .json()
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_to_classic_url() {
+        assert_eq!(
+            classic_url(
+                "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/branches/PR-4/runs/12/"
+            ),
+            "/job/team/job/app/job/PR-4/12/"
+        );
+    }
+
+    #[tokio::test]
+    async fn can_get_runs_of_a_branch() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": true}"#)
+            .create();
+        let plugins = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"plugins": [{"shortName": "blueocean-rest", "active": true}]}"#)
+            .expect(1)
+            .create();
+        let _ = s
+            .mock(
+                "GET",
+                "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/",
+            )
+            .with_body(
+                r#"{
+                    "_class": "io.jenkins.blueocean.rest.impl.pipeline.MultiBranchPipelineImpl",
+                    "_links": {"self": {"href": "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/"}},
+                    "name": "app",
+                    "fullName": "team/app",
+                    "weatherScore": 100
+                }"#,
+            )
+            .create();
+        let _ = s
+            .mock(
+                "GET",
+                "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/runs/",
+            )
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("start".into(), "10".into()),
+                mockito::Matcher::UrlEncoded("limit".into(), "10".into()),
+            ]))
+            .with_body(
+                r#"[{
+                    "_links": {"self": {"href": "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/branches/main/runs/3/"}},
+                    "id": "3",
+                    "pipeline": "main",
+                    "state": "FINISHED",
+                    "result": "SUCCESS",
+                    "durationInMillis": 1200
+                }, {
+                    "_links": {"self": {"href": "/blue/rest/organizations/jenkins/pipelines/team/pipelines/app/branches/main/runs/4/"}},
+                    "id": "4",
+                    "pipeline": "main",
+                    "state": "HIBERNATING",
+                    "result": "PARTIALLY_SUCCESSFUL"
+                }]"#,
+            )
+            .create();
+
+        let pipeline = jenkins_client.get_blue_pipeline("team/app").await.unwrap();
+        let runs = pipeline
+            .get_runs(&jenkins_client, Pagination::with_limit(10).next())
+            .await
+            .unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].result, Some(BlueRunResult::Success));
+        assert_eq!(runs[1].state, Some(BlueRunState::Unknown));
+        assert_eq!(runs[1].result, Some(BlueRunResult::Unknown));
+        let build = runs[0].to_short_build(&jenkins_client).unwrap();
+        assert_eq!(build.number, 3);
+        assert_eq!(
+            build.url,
+            format!("{}/job/team/job/app/job/main/3/", s.url())
+        );
+        let job = pipeline.to_short_job(&jenkins_client);
+        assert_eq!(job.url, format!("{}/job/team/job/app/", s.url()));
+        plugins.assert();
+    }
+
+    #[test]
+    fn run_without_build_number_is_rejected() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://localhost:8080")
+            .build()
+            .unwrap();
+        let run: BlueRun = serde_json::from_str(
+            r#"{
+                "_links": {"self": {"href": "/blue/rest/organizations/jenkins/pipelines/app/runs/abc/"}},
+                "id": "abc",
+                "pipeline": "app"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            run.to_short_build(&jenkins_client)
+                .unwrap_err()
+                .downcast_ref::<client::Error>(),
+            Some(client::Error::InvalidUrl {
+                expected: client::error::ExpectedType::Build,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn blue_ocean_is_required() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": true}"#)
+            .create();
+        let _ = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"plugins": [{"shortName": "git", "active": true}]}"#)
+            .create();
+
+        let error = jenkins_client
+            .get_blue_pipelines(Pagination::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<client::Error>(),
+            Some(client::Error::MissingCapability {
+                capability: Capability::BlueOcean
+            })
+        ));
+    }
+}
@@ -1,13 +1,21 @@
 use std::marker::PhantomData;
+use std::path::{Component, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use reqwest::StatusCode;
 use serde::{self, Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
 
 use crate::helpers::Class;
 
 use crate::Jenkins;
 use crate::action::CommonAction;
 use crate::client::{self, Result};
+use crate::client_internals::check_permission;
 use crate::client_internals::path::{Name, Path};
+use crate::fingerprint::Fingerprint;
 use crate::job::{CommonJob, Job};
 
 /// Short Build that is used in lists and links from other structs
@@ -32,7 +40,7 @@ pub struct ShortBuild<T: Build = CommonBuild> {
     pub extra_fields: Option<serde_json::Value>,
 
     #[serde(skip)]
-    build_type: PhantomData<T>,
+    pub(crate) build_type: PhantomData<T>,
 }
 impl<T> ShortBuild<T>
 where
@@ -85,6 +93,51 @@ pub struct Artifact {
     pub relative_path: String,
 }
 
+/// Content of an artifact, streamed from Jenkins as chunks of bytes
+pub struct ArtifactStream {
+    content_length: Option<u64>,
+    chunks: Pin<Box<dyn Stream<Item = reqwest::Result<bytes::Bytes>> + Send>>,
+}
+impl ArtifactStream {
+    fn from_response(response: reqwest::Response) -> Self {
+        ArtifactStream {
+            content_length: response.content_length(),
+            chunks: Box::pin(response.bytes_stream()),
+        }
+    }
+
+    /// Size of the artifact in bytes, if announced by Jenkins
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+}
+impl Stream for ArtifactStream {
+    type Item = Result<bytes::Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunks
+            .as_mut()
+            .poll_next(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map_err(Into::into)))
+    }
+}
+impl std::fmt::Debug for ArtifactStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ArtifactStream")
+            .field("content_length", &self.content_length)
+            .finish()
+    }
+}
+
+/// Encode each segment of the relative path of an artifact, keeping the `/` separators
+fn encode_relative_path(relative_path: &str) -> String {
+    relative_path
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Helper type to act on a build
 #[derive(Debug, PartialEq, Clone)]
 pub enum BuildNumber {
@@ -167,6 +220,95 @@ into_buildnumber!(i16);
 into_buildnumber!(i32);
 into_buildnumber!(i64);
 
+/// Get the path to an action on the `Build` at `url`, looking through folders
+fn build_action_path<'a, F>(jenkins_client: &Jenkins, url: &'a str, action: F) -> Result<Path<'a>>
+where
+    F: FnOnce(Name<'a>, BuildNumber, Option<Name<'a>>) -> Path<'a>,
+{
+    jenkins_client
+        .url_to_path(url)
+        .map_innermost(|path| match path {
+            Path::Build {
+                job_name,
+                number,
+                configuration,
+            } => Some(action(job_name, number, configuration)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            client::Error::InvalidUrl {
+                url: url.to_string(),
+                expected: client::error::ExpectedType::Build,
+            }
+            .into()
+        })
+}
+
+/// Get the path to an artifact of the `Build` at `url`, from its encoded relative path
+fn artifact_path<'a>(
+    jenkins_client: &Jenkins,
+    url: &'a str,
+    relative_path: &'a str,
+) -> Result<Path<'a>> {
+    build_action_path(jenkins_client, url, |job_name, number, configuration| {
+        Path::Artifact {
+            job_name,
+            number,
+            configuration,
+            relative_path: Name::UrlEncodedName(relative_path),
+        }
+    })
+}
+
+/// Extension added to the name of a partially downloaded artifact to get the file keeping its
+/// validator
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// Get the range of a response to a range request from its `Content-Range` header, formatted
+/// like `bytes */1234` or `bytes 0-99/1234`: the first byte if there is one, and the complete
+/// length of the resource
+fn content_range(response: &reqwest::Response) -> Option<(Option<u64>, u64)> {
+    let (range, length) = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split_once('/')?;
+    let start = range
+        .split_once('-')
+        .and_then(|(start, _)| start.parse().ok());
+    Some((start, length.parse().ok()?))
+}
+
+/// Get the validator of a response that can be sent back in an `If-Range` header: a strong
+/// `ETag`, or the `Last-Modified` date
+fn validator(response: &reqwest::Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(reqwest::header::ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(reqwest::header::LAST_MODIFIED))
+        .and_then(|validator| validator.to_str().ok())
+        .map(ToString::to_string)
+}
+
+#[derive(Deserialize)]
+struct BuildDescription {
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BuildArtifacts {
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Deserialize)]
+struct BuildFingerprints {
+    #[serde(default)]
+    fingerprint: Vec<Fingerprint>,
+}
+
 /// Trait implemented by specializations of `Build` and providing common methods
 pub trait Build: Sync {
     /// Type of the job that triggered this build
@@ -286,30 +428,332 @@ pub trait Build: Sync {
         artifact: &Artifact,
     ) -> impl Future<Output = Result<bytes::Bytes>> + Send {
         async {
-            let path = jenkins_client.url_to_path(self.url());
-            if let Path::Build {
-                job_name,
-                number,
-                configuration,
-            } = path
-            {
-                return Ok(jenkins_client
-                    .get_blob(&Path::Artifact {
-                        job_name,
-                        number,
-                        configuration,
-                        relative_path: Name::Name(&artifact.relative_path),
-                    })
-                    .await?
-                    .bytes()
-                    .await?);
+            let relative_path = encode_relative_path(&artifact.relative_path);
+            let path = artifact_path(jenkins_client, self.url(), &relative_path)?;
+            Ok(jenkins_client.get_blob(&path).await?.bytes().await?)
+        }
+    }
+
+    /// Stream an artifact's contents from a `Build`, without buffering it in memory
+    fn stream_artifact(
+        &self,
+        jenkins_client: &Jenkins,
+        artifact: &Artifact,
+    ) -> impl Future<Output = Result<ArtifactStream>> + Send {
+        async {
+            let relative_path = encode_relative_path(&artifact.relative_path);
+            let path = artifact_path(jenkins_client, self.url(), &relative_path)?;
+            Ok(ArtifactStream::from_response(
+                jenkins_client.get_blob(&path).await?,
+            ))
+        }
+    }
+
+    /// Stream all the artifacts of a `Build` as a zip archive
+    fn get_artifacts_zip(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> impl Future<Output = Result<ArtifactStream>> + Send {
+        async {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::ArtifactsZip {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            Ok(ArtifactStream::from_response(
+                jenkins_client.get_blob(&path).await?,
+            ))
+        }
+    }
+
+    /// Download an artifact to `directory`, keeping its relative path. An interrupted download
+    /// resumes from the current size of the file if the artifact didn't change, and a file that
+    /// doesn't match the artifact is downloaded again. Returns the path to the file
+    fn download_artifact_to(
+        &self,
+        jenkins_client: &Jenkins,
+        artifact: &Artifact,
+        directory: &std::path::Path,
+    ) -> impl Future<Output = Result<PathBuf>> + Send {
+        async move {
+            let target: PathBuf = std::path::Path::new(&artifact.relative_path)
+                .components()
+                .filter(|component| matches!(component, Component::Normal(_)))
+                .fold(directory.to_path_buf(), |target, component| {
+                    target.join(component)
+                });
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
             }
+            let mut partial = target.clone().into_os_string();
+            partial.push(".");
+            partial.push(PARTIAL_EXTENSION);
+            let partial = PathBuf::from(partial);
+            let offset = match tokio::fs::metadata(&target).await {
+                Ok(metadata) => metadata.len(),
+                Err(_) => 0,
+            };
+            // only an interrupted download has a validator, to check the artifact didn't change
+            let if_range = match offset {
+                0 => None,
+                _ => tokio::fs::read_to_string(&partial).await.ok(),
+            };
 
-            Err(client::Error::InvalidUrl {
-                url: self.url().to_string(),
-                expected: client::error::ExpectedType::Build,
+            let relative_path = encode_relative_path(&artifact.relative_path);
+            let path = artifact_path(jenkins_client, self.url(), &relative_path)?;
+            let mut response = jenkins_client
+                .get_blob_from(&path, offset, if_range.as_deref())
+                .await?;
+            let resumed = match response.status() {
+                StatusCode::RANGE_NOT_SATISFIABLE => {
+                    if if_range.is_none()
+                        && content_range(&response).map(|(_, length)| length) == Some(offset)
+                    {
+                        // the file was already downloaded completely
+                        return Ok(target);
+                    }
+                    false
+                }
+                StatusCode::PARTIAL_CONTENT => {
+                    if_range.is_some()
+                        && content_range(&response).and_then(|(start, _)| start) == Some(offset)
+                }
+                _ => false,
+            };
+            if !resumed {
+                if response.status() != StatusCode::OK {
+                    // the local file doesn't match the artifact, download it again
+                    response = jenkins_client.get_blob_from(&path, 0, None).await?;
+                }
+                match validator(&response) {
+                    Some(validator) => tokio::fs::write(&partial, validator).await?,
+                    None => {
+                        let _ = tokio::fs::remove_file(&partial).await;
+                    }
+                }
             }
-            .into())
+            let mut file = if resumed {
+                tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(&target)
+                    .await?
+            } else {
+                tokio::fs::File::create(&target).await?
+            };
+            let mut chunks = response.bytes_stream();
+            while let Some(chunk) = chunks.next().await {
+                file.write_all(&chunk?).await?;
+            }
+            file.flush().await?;
+            let _ = tokio::fs::remove_file(&partial).await;
+            Ok(target)
+        }
+    }
+
+    /// Download all the artifacts of a `Build` to `directory`, with at most `concurrency`
+    /// downloads at the same time. Partially downloaded files are resumed. Returns the paths
+    /// to the files
+    fn download_artifacts_to(
+        &self,
+        jenkins_client: &Jenkins,
+        directory: &std::path::Path,
+        concurrency: usize,
+    ) -> impl Future<Output = Result<Vec<PathBuf>>> + Send {
+        async move {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let build: BuildArtifacts = jenkins_client
+                .get_with_params(
+                    &path,
+                    [("tree", "artifacts[displayPath,fileName,relativePath]")],
+                )
+                .await?
+                .json()
+                .await?;
+
+            let downloads: Vec<_> = build
+                .artifacts
+                .iter()
+                .map(|artifact| self.download_artifact_to(jenkins_client, artifact, directory))
+                .collect();
+            futures_util::stream::iter(downloads)
+                .buffer_unordered(concurrency.max(1))
+                .try_collect()
+                .await
+        }
+    }
+
+    /// Get the fingerprints of the files produced or used by a `Build`
+    fn fingerprints(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> impl Future<Output = Result<Vec<Fingerprint>>> + Send {
+        async {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let build: BuildFingerprints = jenkins_client
+                .get_with_params(
+                    &path,
+                    [(
+                        "tree",
+                        "fingerprint[fileName,hash,timestamp,original[name,number],usage[name,ranges[ranges[start,end]]]]",
+                    )],
+                )
+                .await?
+                .json()
+                .await?;
+            Ok(build.fingerprint)
+        }
+    }
+
+    /// Delete this `Build`
+    ///
+    /// # Errors
+    /// Returns an [`Error::MissingPermission`](../client/enum.Error.html#variant.MissingPermission)
+    /// if the user is not allowed to delete builds of this job
+    fn delete(&self, jenkins_client: &Jenkins) -> impl Future<Output = Result<()>> + Send {
+        async {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::BuildDelete {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let _ = check_permission(jenkins_client.post(&path).await, "Run/Delete")?;
+            Ok(())
+        }
+    }
+
+    /// Toggle whether this `Build` is kept forever, as reported by `keep_log`. It may need to
+    /// be refreshed as it may have been updated
+    ///
+    /// # Errors
+    /// Returns an [`Error::MissingPermission`](../client/enum.Error.html#variant.MissingPermission)
+    /// if the user is not allowed to update builds of this job
+    fn toggle_keep_log(&self, jenkins_client: &Jenkins) -> impl Future<Output = Result<()>> + Send {
+        async {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::BuildToggleKeepLog {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let _ = check_permission(jenkins_client.post(&path).await, "Run/Update")?;
+            Ok(())
+        }
+    }
+
+    /// Set the description of this `Build`. It may need to be refreshed as it may have been
+    /// updated
+    ///
+    /// # Errors
+    /// Returns an [`Error::MissingPermission`](../client/enum.Error.html#variant.MissingPermission)
+    /// if the user is not allowed to update builds of this job
+    fn set_description(
+        &self,
+        jenkins_client: &Jenkins,
+        description: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::BuildSubmitDescription {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let _ = check_permission(
+                jenkins_client
+                    .post_with_body(
+                        &path,
+                        serde_urlencoded::to_string([("description", description)])?,
+                        &[],
+                    )
+                    .await,
+                "Run/Update",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Set the display name of this `Build`, keeping its description. It may need to be
+    /// refreshed as it may have been updated
+    ///
+    /// # Errors
+    /// Returns an [`Error::MissingPermission`](../client/enum.Error.html#variant.MissingPermission)
+    /// if the user is not allowed to update builds of this job
+    fn set_display_name(
+        &self,
+        jenkins_client: &Jenkins,
+        display_name: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let build_path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            // the configuration form replaces both the display name and the description
+            let current: BuildDescription = jenkins_client
+                .get_with_params(&build_path, [("tree", "description")])
+                .await?
+                .json()
+                .await?;
+
+            let path = build_action_path(
+                jenkins_client,
+                self.url(),
+                |job_name, number, configuration| Path::BuildConfigSubmit {
+                    job_name,
+                    number,
+                    configuration,
+                },
+            )?;
+            let form = serde_json::json!({
+                "displayName": display_name,
+                "description": current.description.unwrap_or_default(),
+            });
+            let _ = check_permission(
+                jenkins_client
+                    .post_with_body(
+                        &path,
+                        serde_urlencoded::to_string([("json", form.to_string())])?,
+                        &[],
+                    )
+                    .await,
+                "Run/Update",
+            )?;
+            Ok(())
         }
     }
 }
@@ -436,3 +880,179 @@ build_with_common_fields_and_impl!(
 specialize!(CommonBuild => Build);
 
 impl CommonBuild {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_at(server_url: &str) -> CommonBuild {
+        serde_json::from_value(serde_json::json!({
+            "url": format!("{server_url}/job/a/1/"),
+            "number": 1,
+            "duration": 0,
+            "estimatedDuration": 0,
+            "timestamp": 0,
+            "keepLog": false,
+            "result": "SUCCESS",
+            "displayName": "#1",
+            "building": false,
+            "id": "1",
+            "queueId": 1,
+            "actions": [],
+            "artifacts": [],
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn can_get_build_fingerprints() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+        let build = build_at(&s.url());
+
+        let _ = s
+            .mock("GET", "/job/a/1/api/json")
+            .match_query(mockito::Matcher::Regex("tree=fingerprint".to_string()))
+            .with_body(
+                r#"{"fingerprint": [{
+                    "fileName": "app.jar",
+                    "hash": "0123456789abcdef0123456789abcdef",
+                    "original": {"name": "a", "number": 1},
+                    "timestamp": 1700000000000,
+                    "usage": [{"name": "a", "ranges": {"ranges": [{"start": 1, "end": 2}]}}]
+                }]}"#,
+            )
+            .create();
+
+        let fingerprints = build.fingerprints(&jenkins_client).await.unwrap();
+        assert_eq!(fingerprints.len(), 1);
+        assert_eq!(fingerprints[0].hash, "0123456789abcdef0123456789abcdef");
+        assert_eq!(
+            fingerprints[0].usage[0].build_numbers().collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+
+    #[tokio::test]
+    async fn download_restarts_when_local_file_does_not_match() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let build = build_at(&s.url());
+        let artifact = Artifact {
+            display_path: None,
+            file_name: "out.txt".to_string(),
+            relative_path: "out.txt".to_string(),
+        };
+
+        let directory =
+            std::env::temp_dir().join(format!("jenkins-api-download-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("out.txt"), "stale content").unwrap();
+
+        let _ = s
+            .mock("GET", "/job/a/1/artifact/out.txt")
+            .match_header("range", "bytes=13-")
+            .with_status(416)
+            .with_header("content-range", "bytes */5")
+            .create();
+        let full = s
+            .mock("GET", "/job/a/1/artifact/out.txt")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_body("fresh")
+            .create();
+
+        let target = build
+            .download_artifact_to(&jenkins_client, &artifact, &directory)
+            .await
+            .unwrap();
+
+        full.assert();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "fresh");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn download_restarts_when_local_file_is_not_a_prefix() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let build = build_at(&s.url());
+        let artifact = Artifact {
+            display_path: None,
+            file_name: "out.txt".to_string(),
+            relative_path: "out.txt".to_string(),
+        };
+
+        let directory =
+            std::env::temp_dir().join(format!("jenkins-api-prefix-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("out.txt"), "old").unwrap();
+
+        let _ = s
+            .mock("GET", "/job/a/1/artifact/out.txt")
+            .match_header("range", "bytes=3-")
+            .with_status(206)
+            .with_header("content-range", "bytes 3-12/13")
+            .with_body("sh content")
+            .create();
+        let full = s
+            .mock("GET", "/job/a/1/artifact/out.txt")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body("fresh content")
+            .create();
+
+        let target = build
+            .download_artifact_to(&jenkins_client, &artifact, &directory)
+            .await
+            .unwrap();
+
+        full.assert();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "fresh content");
+        assert!(!directory.join("out.txt.partial").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn download_resumes_when_artifact_did_not_change() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let build = build_at(&s.url());
+        let artifact = Artifact {
+            display_path: None,
+            file_name: "out.txt".to_string(),
+            relative_path: "out.txt".to_string(),
+        };
+
+        let directory =
+            std::env::temp_dir().join(format!("jenkins-api-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("out.txt"), "fresh ").unwrap();
+        std::fs::write(directory.join("out.txt.partial"), "\"v1\"").unwrap();
+
+        let rest = s
+            .mock("GET", "/job/a/1/artifact/out.txt")
+            .match_header("range", "bytes=6-")
+            .match_header("if-range", "\"v1\"")
+            .with_status(206)
+            .with_header("content-range", "bytes 6-12/13")
+            .with_body("content")
+            .create();
+
+        let target = build
+            .download_artifact_to(&jenkins_client, &artifact, &directory)
+            .await
+            .unwrap();
+
+        rest.assert();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "fresh content");
+        assert!(!directory.join("out.txt.partial").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
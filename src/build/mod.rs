@@ -7,7 +7,9 @@ use crate::job::JobName;
 
 #[macro_use]
 mod common;
-pub use self::common::{Artifact, Build, BuildNumber, BuildStatus, CommonBuild, ShortBuild};
+pub use self::common::{
+    Artifact, ArtifactStream, Build, BuildNumber, BuildStatus, CommonBuild, ShortBuild,
+};
 mod flow;
 pub use self::flow::BuildFlowRun;
 mod freestyle;
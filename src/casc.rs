@@ -0,0 +1,203 @@
+//! Jenkins Configuration as Code, from the `configuration-as-code` plugin
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+
+use crate::Jenkins;
+use crate::client::{self, Result, error::CascIssue};
+use crate::client_internals::{JAVA_EXCEPTION, Path, SecretBody, check_permission};
+use crate::helpers::unescape_xml;
+
+/// Location of an error in the YAML, as reported by the YAML parser
+static LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bline (\d+)").unwrap());
+
+/// An issue, as returned by the check of a configuration
+#[derive(Debug, Deserialize)]
+struct CheckIssue {
+    line: Option<u32>,
+    warning: String,
+}
+
+impl Jenkins {
+    /// Post a configuration, replacing an exception thrown by Jenkins while reading it by an
+    /// [`Error::InvalidCasc`](../client/enum.Error.html#variant.InvalidCasc)
+    async fn post_casc(&self, path: &Path<'_>, yaml: &str) -> Result<Response> {
+        let response = self
+            .post_yaml(path, SecretBody(yaml.to_string()), &[])
+            .await?;
+        if response.status() != StatusCode::INTERNAL_SERVER_ERROR {
+            return Jenkins::error_for_status(response);
+        }
+        // get the error before reading the body. In this case it can't be OK
+        let error = match response.error_for_status_ref() {
+            Ok(_) => unreachable!(),
+            Err(err) => err,
+        };
+        let body = response.text().await?;
+        match Jenkins::find_java_exception(&JAVA_EXCEPTION, &body) {
+            Some((_, message)) => {
+                let message = unescape_xml(message);
+                Err(client::Error::InvalidCasc {
+                    issues: vec![CascIssue {
+                        line: LINE
+                            .captures(&message)
+                            .and_then(|captures| captures[1].parse().ok()),
+                        message,
+                    }],
+                }
+                .into())
+            }
+            None => Err(error.into()),
+        }
+    }
+
+    /// Export the current configuration of Jenkins as YAML
+    pub async fn export_casc(&self) -> Result<String> {
+        Ok(
+            check_permission(self.post(&Path::CascExport).await, "Overall/Administer")?
+                .text()
+                .await?,
+        )
+    }
+
+    /// Check a configuration without applying it, returning the issues found. The
+    /// configuration can be applied if there are none
+    ///
+    /// # Errors
+    /// If Jenkins can't read the configuration, this will return an
+    /// [`Error::InvalidCasc`](../client/enum.Error.html#variant.InvalidCasc)
+    pub async fn check_casc(&self, yaml: &str) -> Result<Vec<CascIssue>> {
+        let issues: Vec<CheckIssue> = check_permission(
+            self.post_casc(&Path::CascCheck, yaml).await,
+            "Overall/Administer",
+        )?
+        .json()
+        .await?;
+        Ok(issues
+            .into_iter()
+            .map(|issue| CascIssue {
+                line: issue.line,
+                message: issue.warning,
+            })
+            .collect())
+    }
+
+    /// Apply a configuration to Jenkins
+    ///
+    /// # Errors
+    /// If Jenkins can't read or apply the configuration, this will return an
+    /// [`Error::InvalidCasc`](../client/enum.Error.html#variant.InvalidCasc)
+    pub async fn apply_casc(&self, yaml: &str) -> Result<()> {
+        let _ = check_permission(
+            self.post_casc(&Path::CascApply, yaml).await,
+            "Overall/Administer",
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_check_casc() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/configuration-as-code/check")
+            .match_header("content-type", "application/x-yaml")
+            .match_body("jenkins:\n  numExecutors: 2\n")
+            .with_body(r#"[{"line": 2, "warning": "'numExecutors' is deprecated"}]"#)
+            .create();
+
+        let issues = jenkins_client
+            .check_casc("jenkins:\n  numExecutors: 2\n")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            issues,
+            vec![CascIssue {
+                line: Some(2),
+                message: "'numExecutors' is deprecated".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_casc_with_invalid_configuration() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/configuration-as-code/apply")
+            .with_status(500)
+            .with_body(
+                "<pre>io.jenkins.plugins.casc.UnknownAttributesException: jenkins: \
+                 Invalid configuration elements for type class jenkins.model.Jenkins : \
+                 foo.\n\tat io.jenkins.plugins.casc.BaseConfigurator.configure</pre>",
+            )
+            .create();
+
+        let error = jenkins_client
+            .apply_casc("jenkins:\n  foo: bar\n")
+            .await
+            .unwrap_err();
+
+        match error.downcast_ref::<client::Error>() {
+            Some(client::Error::InvalidCasc { issues }) => assert_eq!(
+                issues[0],
+                CascIssue {
+                    line: None,
+                    message: "jenkins: Invalid configuration elements for type class \
+                              jenkins.model.Jenkins : foo."
+                        .to_string(),
+                }
+            ),
+            _ => panic!("unexpected error {error}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn check_casc_with_invalid_yaml() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/configuration-as-code/check")
+            .with_status(500)
+            .with_body(
+                "<pre>org.yaml.snakeyaml.scanner.ScannerException: mapping values are not \
+                 allowed here in 'reader', line 2, column 14:\n\tat \
+                 org.yaml.snakeyaml.scanner.ScannerImpl.fetchValue</pre>",
+            )
+            .create();
+
+        let error = jenkins_client
+            .check_casc("jenkins:\n  systemMessage: a: b\n")
+            .await
+            .unwrap_err();
+
+        match error.downcast_ref::<client::Error>() {
+            Some(client::Error::InvalidCasc { issues }) => assert_eq!(issues[0].line, Some(2)),
+            _ => panic!("unexpected error {error}"),
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{str::FromStr, time::Duration};
+use std::{
+    str::FromStr,
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
 
 use reqwest::{self, Client, Url};
 
@@ -67,6 +71,9 @@ impl JenkinsBuilder {
             user: self.user,
             csrf_enabled: self.csrf_enabled,
             depth: self.depth,
+            version: OnceLock::new(),
+            crumbs_required: OnceLock::new(),
+            capabilities: RwLock::new(None),
         })
     }
 
@@ -109,7 +116,7 @@ mod tests {
 
         assert_eq!(jenkins_client.url, JENKINS_URL);
         assert_eq!(jenkins_client.user, None);
-        assert_eq!(jenkins_client.csrf_enabled, true);
+        assert!(jenkins_client.csrf_enabled);
     }
 
     #[test]
@@ -118,7 +125,7 @@ mod tests {
 
         assert_eq!(jenkins_client.url, JENKINS_URL);
         assert_eq!(jenkins_client.user, None);
-        assert_eq!(jenkins_client.csrf_enabled, true);
+        assert!(jenkins_client.csrf_enabled);
     }
 
     #[test]
@@ -127,6 +134,6 @@ mod tests {
 
         assert_eq!(jenkins_client.url, JENKINS_URL);
         assert_eq!(jenkins_client.user, None);
-        assert_eq!(jenkins_client.csrf_enabled, false);
+        assert!(!jenkins_client.csrf_enabled);
     }
 }
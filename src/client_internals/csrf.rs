@@ -15,8 +15,9 @@ impl Jenkins {
     pub(crate) async fn add_csrf_to_request(
         &self,
         request_builder: RequestBuilder,
+        path: &Path<'_>,
     ) -> Result<RequestBuilder> {
-        if self.csrf_enabled {
+        if self.csrf_enabled && !path.is_token_authenticated() && self.crumbs_required().await {
             let crumb = self.get_csrf().await?;
             Ok(request_builder.header(
                 HeaderName::from_lowercase(crumb.crumb_request_field.to_lowercase().as_bytes())?,
@@ -38,8 +38,96 @@ pub enum Error {
         message: String,
     },
 
+    #[error("missing permission '{permission}'")]
+    ///  Error when the user doesn't have the permission required for an action
+    MissingPermission {
+        /// Permission required, as named by Jenkins
+        permission: &'static str,
+    },
+
+    #[error(
+        "invalid build parameters: {}",
+        issues.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    ///  Error when build parameters don't match the parameters definitions of the job
+    InvalidBuildParameters {
+        /// Issues found with the parameters
+        issues: Vec<ParameterIssue>,
+    },
+
+    #[error(
+        "invalid pipeline: {}",
+        errors.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    ///  Error when Jenkins can't parse a declarative pipeline
+    InvalidPipeline {
+        /// Errors found in the pipeline
+        errors: Vec<PipelineError>,
+    },
+
+    #[error(
+        "invalid configuration: {}",
+        issues.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    ///  Error when Jenkins can't apply a Configuration as Code YAML
+    InvalidCasc {
+        /// Issues found in the configuration
+        issues: Vec<CascIssue>,
+    },
+
+    #[error("error from Jenkins: '{message}'")]
+    ///  Error when Jenkins replies with an error status and a message
+    ErrorResponse {
+        /// Message provided by Jenkins
+        message: String,
+    },
+
+    #[error("script failed with {exception}: '{message}'")]
+    ///  Error when a script run in the script console throws an exception
+    ScriptException {
+        /// Class of the exception
+        exception: String,
+        /// Exception message
+        message: String,
+        /// Output of the script, including the stack trace
+        output: String,
+    },
+
+    #[error("timed out waiting for {operation}")]
+    ///  Error when Jenkins didn't reach the expected state in time
+    Timeout {
+        /// What was waited for
+        operation: &'static str,
+    },
+
+    #[error("invalid Jenkins version '{version}'")]
+    ///  Error when the version of Jenkins is missing or can't be parsed
+    InvalidVersion {
+        /// Version provided by Jenkins
+        version: String,
+    },
+
+    #[error("Jenkins doesn't provide the {capability}")]
+    ///  Error when Jenkins doesn't provide a feature required for an action
+    MissingCapability {
+        /// Capability required
+        capability: crate::version::Capability,
+    },
+
+    #[error("invalid fingerprint '{md5}', expected a MD5 checksum of 32 hexadecimal digits")]
+    ///  Error when a fingerprint is requested with an invalid checksum
+    InvalidFingerprint {
+        /// Checksum provided
+        md5: String,
+    },
+
+    #[error("invalid JNLP file, expected the secret and the name of the agent")]
+    ///  Error when the JNLP file of an agent doesn't have the expected arguments
+    InvalidJnlp,
+
     #[error("can't build a job remotely with parameters")]
     ///  Error when trying to remotely build a job with parameters
+    #[deprecated(note = "building a job remotely with parameters is now supported")]
     UnsupportedBuildConfiguration,
 
     #[error("can't do '{action}' on a {object_type} of type {variant_name}")]
@@ -54,6 +142,116 @@ pub enum Error {
     },
 }
 
+/// Replace a `403 Forbidden` error from Jenkins by an
+/// [`Error::MissingPermission`](enum.Error.html#variant.MissingPermission)
+pub(crate) fn check_permission<T>(result: Result<T>, permission: &'static str) -> Result<T> {
+    result.map_err(|err| {
+        match err
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+        {
+            Some(reqwest::StatusCode::FORBIDDEN) => Error::MissingPermission { permission }.into(),
+            _ => err,
+        }
+    })
+}
+
+/// Issue found when validating build parameters against the parameters definitions of a job
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterIssue {
+    /// The job doesn't define a parameter with this name
+    UnknownParameter {
+        /// Name of the parameter
+        name: String,
+    },
+    /// The value is not one of the allowed choices
+    InvalidChoice {
+        /// Name of the parameter
+        name: String,
+        /// Value provided
+        value: String,
+        /// Values allowed
+        choices: Vec<String>,
+    },
+    /// The value doesn't have the type of the parameter
+    InvalidType {
+        /// Name of the parameter
+        name: String,
+        /// Type of the parameter, as displayed by Jenkins
+        expected: String,
+    },
+    /// The parameter has no default value, and no value was provided
+    MissingValue {
+        /// Name of the parameter
+        name: String,
+    },
+}
+
+impl fmt::Display for ParameterIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParameterIssue::UnknownParameter { ref name } => {
+                write!(f, "unknown parameter '{name}'")
+            }
+            ParameterIssue::InvalidChoice {
+                ref name,
+                ref value,
+                ref choices,
+            } => write!(
+                f,
+                "invalid value '{value}' for parameter '{name}', expected one of '{}'",
+                choices.join("', '")
+            ),
+            ParameterIssue::InvalidType {
+                ref name,
+                ref expected,
+            } => write!(f, "invalid value for parameter '{name}' of type {expected}"),
+            ParameterIssue::MissingValue { ref name } => {
+                write!(f, "missing value for parameter '{name}'")
+            }
+        }
+    }
+}
+
+/// Error found when validating a declarative pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineError {
+    /// Message of the error
+    pub message: String,
+    /// Line of the error, starting at 1
+    pub line: Option<u32>,
+    /// Column of the error, starting at 1
+    pub column: Option<u32>,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} at {line}:{column}", self.message),
+            (Some(line), None) => write!(f, "{} at line {line}", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Issue found in a Configuration as Code YAML
+#[derive(Debug, Clone, PartialEq)]
+pub struct CascIssue {
+    /// Line of the issue in the YAML, if known
+    pub line: Option<u32>,
+    /// Message of the issue
+    pub message: String,
+}
+
+impl fmt::Display for CascIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} at line {line}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 /// Possible type of URL expected in links between items
 #[derive(Debug, Copy, Clone)]
 pub enum ExpectedType {
@@ -69,6 +267,10 @@ pub enum ExpectedType {
     ShortView,
     /// a `MavenArtifactRecord`
     MavenArtifactRecord,
+    /// a `User`
+    User,
+    /// a `Computer`
+    Computer,
 }
 
 impl fmt::Display for ExpectedType {
@@ -80,6 +282,8 @@ impl fmt::Display for ExpectedType {
             ExpectedType::View => write!(f, "View"),
             ExpectedType::ShortView => write!(f, "ShortView"),
             ExpectedType::MavenArtifactRecord => write!(f, "MavenArtifactRecord"),
+            ExpectedType::User => write!(f, "User"),
+            ExpectedType::Computer => write!(f, "Computer"),
         }
     }
 }
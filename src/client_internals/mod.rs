@@ -2,16 +2,19 @@
 
 use std::fmt::Debug;
 use std::string::ToString;
+use std::sync::{LazyLock, OnceLock, RwLock};
 
 use log::{debug, warn};
 use regex::Regex;
 use reqwest::{
     Body, Client, RequestBuilder, Response, StatusCode,
-    header::{CONTENT_TYPE, HeaderValue, USER_AGENT},
+    header::{CONTENT_TYPE, HeaderValue, IF_RANGE, RANGE, USER_AGENT},
+    multipart::Form,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 mod errors;
+pub(crate) use self::errors::check_permission;
 pub use self::errors::{Error, Result};
 mod builder;
 pub mod path;
@@ -24,7 +27,61 @@ pub use self::tree::{TreeBuilder, TreeQueryParam};
 /// Helper type for error management
 pub mod error {
     pub use super::errors::Action;
+    pub use super::errors::CascIssue;
     pub use super::errors::ExpectedType;
+    pub use super::errors::ParameterIssue;
+    pub use super::errors::PipelineError;
+}
+
+/// A `java.lang` exception, anywhere in a page
+static JAVA_LANG_EXCEPTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"java.lang.([a-zA-Z]+): (.*)").unwrap());
+
+/// Any Java exception, anywhere in a page, with its fully qualified class name and its
+/// optional message
+pub(crate) static JAVA_EXCEPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"((?:[a-z_$][\w$]*\.)+[A-Z][\w$]*(?:Exception|Error))(?:: ([^\r\n<]*))?").unwrap()
+});
+
+/// Is a line part of a stack trace, after the line with the exception: a frame, elided frames
+/// or a cause
+fn is_stack_trace_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    (trimmed.len() < line.len() && (trimmed.starts_with("at ") || trimmed.starts_with("... ")))
+        || trimmed.starts_with("Caused by: ")
+}
+
+/// Response of a descriptor method or a plugin action, wrapping its data with a status
+#[derive(Debug, Deserialize)]
+pub(crate) struct DescriptorResponse<T> {
+    status: String,
+    message: Option<String>,
+    data: Option<T>,
+}
+impl<T> DescriptorResponse<T> {
+    pub(crate) fn into_result(self) -> Result<Option<T>> {
+        if self.status == "ok" {
+            Ok(self.data)
+        } else {
+            Err(Error::ErrorResponse {
+                message: self.message.unwrap_or(self.status),
+            }
+            .into())
+        }
+    }
+}
+
+/// Body of a request containing secrets, redacted when the request is logged
+pub(crate) struct SecretBody(pub(crate) String);
+impl Debug for SecretBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("SecretBody").field(&"***").finish()
+    }
+}
+impl From<SecretBody> for Body {
+    fn from(body: SecretBody) -> Self {
+        Body::from(body.0)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -41,6 +98,9 @@ pub struct Jenkins {
     user: Option<User>,
     csrf_enabled: bool,
     pub(crate) depth: u8,
+    pub(crate) version: OnceLock<crate::version::JenkinsVersion>,
+    pub(crate) crumbs_required: OnceLock<bool>,
+    pub(crate) capabilities: RwLock<Option<crate::version::Capabilities>>,
 }
 
 /// Advanced query parameters supported by Jenkins to control the amount of data retrieved
@@ -98,7 +158,7 @@ impl Jenkins {
         Ok(self.client.execute(query).await?)
     }
 
-    fn error_for_status(response: Response) -> Result<Response> {
+    pub(crate) fn error_for_status(response: Response) -> Result<Response> {
         let status = response.status();
         if status.is_client_error() || status.is_server_error() {
             warn!("got an error: {status}");
@@ -118,20 +178,53 @@ impl Jenkins {
     ) -> Result<Response> {
         let query = self
             .client
-            .get(&self.url_api_json(&path.to_string()))
+            .get(self.url_api_json(&path.to_string()))
             .query(&qps);
         Self::error_for_status(self.send(query).await?)
     }
 
     pub(crate) async fn get_blob(&self, path: &Path<'_>) -> Result<Response> {
-        let query = self.client.get(&self.url(&path.to_string()));
+        let query = self.client.get(self.url(&path.to_string()));
         Self::error_for_status(self.send(query).await?)
     }
 
+    pub(crate) async fn get_blob_with_params<T: Serialize>(
+        &self,
+        path: &Path<'_>,
+        qps: T,
+    ) -> Result<Response> {
+        let query = self.client.get(self.url(&path.to_string())).query(&qps);
+        Self::error_for_status(self.send(query).await?)
+    }
+
+    /// Get a blob starting at byte `offset`. With `if_range`, an `ETag` or a `Last-Modified`
+    /// date, the whole blob is returned if it changed. A `416 Range Not Satisfiable` response is
+    /// not considered an error, as it means there is nothing left to get
+    pub(crate) async fn get_blob_from(
+        &self,
+        path: &Path<'_>,
+        offset: u64,
+        if_range: Option<&str>,
+    ) -> Result<Response> {
+        let mut query = self.client.get(self.url(&path.to_string()));
+        if offset > 0 {
+            query = query.header(RANGE, format!("bytes={offset}-"));
+            if let Some(if_range) = if_range {
+                query = query.header(IF_RANGE, if_range);
+            }
+        }
+        let response = self.send(query).await?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            Ok(response)
+        } else {
+            Self::error_for_status(response)
+        }
+    }
+
     pub(crate) async fn post(&self, path: &Path<'_>) -> Result<Response> {
-        let mut request_builder = self.client.post(&self.url(&path.to_string()));
+        let mut request_builder = self.client.post(self.url(&path.to_string()));
 
-        request_builder = self.add_csrf_to_request(request_builder).await?;
+        request_builder = self.add_csrf_to_request(request_builder, path).await?;
 
         Self::error_for_status(self.send(request_builder).await?)
     }
@@ -142,18 +235,134 @@ impl Jenkins {
         body: T,
         qps: &[(&str, &str)],
     ) -> Result<Response> {
-        let mut request_builder = self.client.post(&self.url(&path.to_string()));
+        self.post_with_content_type(path, body, "application/x-www-form-urlencoded", qps)
+            .await
+    }
 
-        request_builder = self.add_csrf_to_request(request_builder).await?;
+    /// Post YAML, returning the response whatever its status for the caller to read the
+    /// error from its body
+    pub(crate) async fn post_yaml<T: Into<Body> + Debug>(
+        &self,
+        path: &Path<'_>,
+        body: T,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        self.send_with_content_type(path, body, "application/x-yaml", qps)
+            .await
+    }
 
-        request_builder = request_builder.header(
-            CONTENT_TYPE,
-            HeaderValue::from_static("application/x-www-form-urlencoded"),
-        );
+    pub(crate) async fn post_xml<T: Into<Body> + Debug>(
+        &self,
+        path: &Path<'_>,
+        body: T,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        self.post_with_content_type(path, body, "application/xml", qps)
+            .await
+    }
+
+    async fn post_with_content_type<T: Into<Body> + Debug>(
+        &self,
+        path: &Path<'_>,
+        body: T,
+        content_type: &'static str,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        Self::error_for_java_exception(
+            self.send_with_content_type(path, body, content_type, qps)
+                .await?,
+        )
+        .await
+    }
+
+    async fn send_with_content_type<T: Into<Body> + Debug>(
+        &self,
+        path: &Path<'_>,
+        body: T,
+        content_type: &'static str,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let mut request_builder = self.client.post(self.url(&path.to_string()));
+
+        request_builder = self.add_csrf_to_request(request_builder, path).await?;
+
+        request_builder =
+            request_builder.header(CONTENT_TYPE, HeaderValue::from_static(content_type));
         debug!("{body:?}");
         request_builder = request_builder.query(qps).body(body);
-        let response = self.send(request_builder).await?;
+        self.send(request_builder).await
+    }
+
+    pub(crate) async fn post_multipart(
+        &self,
+        path: &Path<'_>,
+        form: Form,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let mut request_builder = self.client.post(self.url(&path.to_string()));
+
+        request_builder = self.add_csrf_to_request(request_builder, path).await?;
+
+        debug!("{form:?}");
+        request_builder = request_builder.query(qps).multipart(form);
+        Self::error_for_java_exception(self.send(request_builder).await?).await
+    }
+
+    /// Find a Java exception in `text` with `regex`, returning its name and its message
+    pub(crate) fn find_java_exception<'t>(
+        regex: &Regex,
+        text: &'t str,
+    ) -> Option<(&'t str, &'t str)> {
+        regex.captures(text).map(|captures| {
+            (
+                captures.get(1).map(|v| v.as_str()).unwrap_or("unspecified"),
+                captures.get(2).map(|v| v.as_str()).unwrap_or("no message"),
+            )
+        })
+    }
+
+    /// Run a Groovy script with the script console at `path`, returning its output
+    pub(crate) async fn run_script_at(&self, path: &Path<'_>, script: &str) -> Result<String> {
+        let body = serde_urlencoded::to_string([("script", script)])?;
+        let output = check_permission(
+            self.post_with_body(path, body, &[]).await,
+            "Overall/RunScripts",
+        )?
+        .text()
+        .await?;
+
+        // the script console prints the stack trace of an uncaught exception after the output
+        // of the script instead of failing the request, and without the result of the script.
+        // A stack trace printed by the script followed by more output is not a failure
+        let lines: Vec<&str> = output.trim_end().lines().collect();
+        let exception = match lines.last() {
+            Some(line) if is_stack_trace_line(line) => {
+                let start = lines
+                    .iter()
+                    .rposition(|line| !is_stack_trace_line(line))
+                    .unwrap_or(0);
+                Self::find_java_exception(&JAVA_EXCEPTION, &lines[start..].join("\n"))
+                    .map(|(exception, message)| (exception.to_string(), message.to_string()))
+            }
+            _ => None,
+        };
+        match exception {
+            Some((exception, message)) => {
+                warn!("got an exception from a script: {exception}: {message}");
+                Err(Error::ScriptException {
+                    exception,
+                    message,
+                    output,
+                }
+                .into())
+            }
+            None => Ok(output),
+        }
+    }
 
+    /// Read the Java exception from the body of an internal server error, to return a more
+    /// specific error
+    async fn error_for_java_exception(response: Response) -> Result<Response> {
         if response.status() == StatusCode::INTERNAL_SERVER_ERROR {
             // get the error before reading the body. In this case it can't be OK
             let error = match response.error_for_status_ref() {
@@ -163,45 +372,25 @@ impl Jenkins {
 
             let body = response.text().await?;
 
-            let re = Regex::new(r"java.lang.([a-zA-Z]+): (.*)").unwrap();
-            if let Some(captures) = re.captures(&body) {
-                match captures.get(1).map(|v| v.as_str()) {
-                    Some("IllegalStateException") => {
-                        warn!(
-                            "got an IllegalState error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Err(Error::IllegalState {
-                            message: captures
-                                .get(2)
-                                .map(|v| v.as_str())
-                                .unwrap_or("no message")
-                                .to_string(),
-                        })
-                    }
-                    Some("IllegalArgumentException") => {
-                        warn!(
-                            "got an IllegalArgument error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Err(Error::IllegalArgument {
-                            message: captures
-                                .get(2)
-                                .map(|v| v.as_str())
-                                .unwrap_or("no message")
-                                .to_string(),
-                        })
-                    }
-                    Some(_) => {
-                        warn!(
-                            "got an Unknwon error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Ok(())
-                    }
-                    _ => Ok(()),
-                }?;
-            }
+            match Self::find_java_exception(&JAVA_LANG_EXCEPTION, &body) {
+                Some(("IllegalStateException", message)) => {
+                    warn!("got an IllegalState error: {message}");
+                    Err(Error::IllegalState {
+                        message: message.to_string(),
+                    })
+                }
+                Some(("IllegalArgumentException", message)) => {
+                    warn!("got an IllegalArgument error: {message}");
+                    Err(Error::IllegalArgument {
+                        message: message.to_string(),
+                    })
+                }
+                Some((exception, message)) => {
+                    warn!("got an Unknwon error: java.lang.{exception}: {message}");
+                    Ok(())
+                }
+                None => Ok(()),
+            }?;
             Err(error.into())
         } else {
             Ok(Self::error_for_status(response)?)
@@ -330,6 +519,40 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn can_post_with_body_and_get_error_outside_of_java_lang() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/error-AbortException")
+            .with_status(500)
+            .with_body("hviqsuvnqsodjfsqjdgo hudson.AbortException: my error\nvzfjsd")
+            .create();
+
+        let response = jenkins_client
+            .post_with_body(
+                &super::Path::Raw {
+                    path: "/error-AbortException",
+                },
+                "body",
+                &[],
+            )
+            .await;
+
+        assert_eq!(
+            format!("{:?}", response),
+            format!(
+                "Err(reqwest::Error {{ kind: Status(500, None), url: \"{}/error-AbortException\" }})",
+                s.url()
+            ),
+        );
+    }
+
     #[tokio::test]
     async fn can_post_with_query_params() {
         let mut s = mockito::Server::new_async().await;
@@ -349,4 +572,176 @@ mod tests {
         assert_eq!(response.unwrap().text().await.unwrap(), "ok");
         mock.assert()
     }
+
+    #[tokio::test]
+    async fn can_post_and_get_missing_permission() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s.mock("POST", "/forbidden").with_status(403).create();
+
+        let response = super::check_permission(
+            jenkins_client
+                .post(&super::Path::Raw { path: "/forbidden" })
+                .await,
+            "Run/Delete",
+        );
+
+        assert!(response.is_err());
+        assert_eq!(
+            format!("{:?}", response),
+            r#"Err(MissingPermission { permission: "Run/Delete" })"#
+        );
+    }
+
+    #[tokio::test]
+    async fn can_get_blob_from_offset() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let partial = s
+            .mock("GET", "/partial")
+            .match_header("range", "bytes=4-")
+            .with_status(206)
+            .with_body("rest")
+            .create();
+        let _ = s
+            .mock("GET", "/complete")
+            .match_header("range", "bytes=4-")
+            .with_status(416)
+            .create();
+
+        let response = jenkins_client
+            .get_blob_from(&super::Path::Raw { path: "/partial" }, 4, None)
+            .await;
+        assert_eq!(response.unwrap().text().await.unwrap(), "rest");
+        partial.assert();
+
+        let response = jenkins_client
+            .get_blob_from(&super::Path::Raw { path: "/complete" }, 4, None)
+            .await;
+        assert_eq!(
+            response.unwrap().status(),
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+        );
+    }
+
+    #[test]
+    fn secret_body_is_redacted() {
+        let body = super::SecretBody("<password>hunter2</password>".to_string());
+
+        assert!(!format!("{body:?}").contains("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn can_run_script() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/scriptText")
+            .match_body("script=println%28%22hello%22%29")
+            .with_body("hello\n")
+            .create();
+
+        let output = jenkins_client.run_script("println(\"hello\")").await;
+
+        assert_eq!(output.unwrap(), "hello\n");
+    }
+
+    #[tokio::test]
+    async fn can_run_script_and_get_exception() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/computer/agent/scriptText")
+            .with_body(
+                "groovy.lang.MissingPropertyException: No such property: foo for class: Script1
+\tat org.codehaus.groovy.runtime.ScriptBytecodeAdapter.unwrap(ScriptBytecodeAdapter.java:66)
+\tat Script1.run(Script1.groovy:1)
+",
+            )
+            .create();
+
+        let output = jenkins_client
+            .run_script_at(
+                &super::Path::ComputerScriptText {
+                    name: super::Name::Name("agent"),
+                },
+                "foo",
+            )
+            .await;
+
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "script failed with groovy.lang.MissingPropertyException: \
+             'No such property: foo for class: Script1'"
+        );
+    }
+
+    #[tokio::test]
+    async fn can_run_script_and_get_exception_without_message() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/scriptText")
+            .with_body(
+                "java.io.IOException: disk full
+\tat Script1.run(Script1.groovy:2)
+starting
+java.lang.NullPointerException
+\tat Script1.run(Script1.groovy:4)
+Caused by: java.lang.IllegalStateException: closed
+\t... 1 more
+",
+            )
+            .create();
+
+        let output = jenkins_client.run_script("...").await;
+
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "script failed with java.lang.NullPointerException: 'no message'"
+        );
+    }
+
+    #[tokio::test]
+    async fn printed_stack_trace_is_not_an_exception() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let output = "java.io.IOException: disk full
+\tat Script1.run(Script1.groovy:2)
+recovered
+Result: 0
+";
+        let _ = s.mock("POST", "/scriptText").with_body(output).create();
+
+        let result = jenkins_client.run_script("...").await;
+
+        assert_eq!(result.unwrap(), output);
+    }
 }
@@ -10,6 +10,18 @@ pub enum Name<'a> {
     UrlEncodedName(&'a str),
 }
 
+impl<'a> Name<'a> {
+    /// The name, decoded if it was URL encoded
+    pub(crate) fn decoded(&self) -> String {
+        match *self {
+            Name::Name(name) => name.to_string(),
+            Name::UrlEncodedName(name) => urlencoding::decode(name)
+                .map(|name| name.into_owned())
+                .unwrap_or_else(|_| name.to_string()),
+        }
+    }
+}
+
 impl<'a> std::fmt::Display for Name<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -47,6 +59,52 @@ pub enum Path<'a> {
     BuildJobWithParameters {
         name: Name<'a>,
     },
+    BuildByToken,
+    ScriptText,
+    QuietDown,
+    CancelQuietDown,
+    SafeRestart,
+    Restart,
+    SafeExit,
+    Exit,
+    ComputerScriptText {
+        name: Name<'a>,
+    },
+    ComputerCreateItem,
+    ComputerConfigXML {
+        name: Name<'a>,
+    },
+    ComputerDelete {
+        name: Name<'a>,
+    },
+    ComputerToggleOffline {
+        name: Name<'a>,
+    },
+    ComputerLaunchAgent {
+        name: Name<'a>,
+    },
+    ComputerDisconnect {
+        name: Name<'a>,
+    },
+    ComputerJnlp {
+        name: Name<'a>,
+    },
+    ComputerLoadStatistics {
+        name: Name<'a>,
+    },
+    ComputerExecutorStop {
+        name: Name<'a>,
+        number: u32,
+        one_off: bool,
+    },
+    Label {
+        name: Name<'a>,
+    },
+    LabelLoadStatistics {
+        name: Name<'a>,
+    },
+    OverallLoad,
+    BuildByTokenWithParameters,
     PollSCMJob {
         name: Name<'a>,
     },
@@ -61,6 +119,26 @@ pub enum Path<'a> {
         number: build::BuildNumber,
         configuration: Option<Name<'a>>,
     },
+    BuildDelete {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
+    BuildToggleKeepLog {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
+    BuildSubmitDescription {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
+    BuildConfigSubmit {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
     ConsoleText {
         job_name: Name<'a>,
         number: build::BuildNumber,
@@ -86,10 +164,68 @@ pub enum Path<'a> {
         configuration: Option<Name<'a>>,
         relative_path: Name<'a>,
     },
+    ArtifactsZip {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
     InFolder {
         folder_name: Name<'a>,
         path: Box<Path<'a>>,
     },
+    InView {
+        view_name: Name<'a>,
+        path: Box<Path<'a>>,
+    },
+    CreateView,
+    WhoAmI,
+    User {
+        id: Name<'a>,
+    },
+    AsynchPeople,
+    GenerateApiToken {
+        user_id: Name<'a>,
+    },
+    RevokeApiToken {
+        user_id: Name<'a>,
+    },
+    ViewDelete {
+        name: Name<'a>,
+    },
+    ViewConfigXML {
+        name: Name<'a>,
+    },
+    BluePipelines,
+    BluePipeline {
+        full_name: Name<'a>,
+    },
+    CascExport,
+    CascCheck,
+    CascApply,
+    PipelineModelValidate,
+    PipelineModelToJson,
+    PipelineModelToJenkinsfile,
+    PluginManager,
+    InstallNecessaryPlugins,
+    CheckUpdatesServer,
+    UpdateCenter,
+    Credentials {
+        store: &'static str,
+    },
+    CreateCredentials {
+        store: &'static str,
+    },
+    CredentialConfigXML {
+        store: &'static str,
+        id: Name<'a>,
+    },
+    CredentialDelete {
+        store: &'static str,
+        id: Name<'a>,
+    },
+    Fingerprint {
+        md5: &'a str,
+    },
     Computers,
     Computer {
         name: Name<'a>,
@@ -128,6 +264,49 @@ impl<'a> std::fmt::Display for Path<'a> {
                 Path::BuildJobWithParameters { ref name } => {
                     format!("/job/{name}/buildWithParameters")
                 }
+                Path::BuildByToken => "/buildByToken/build".to_string(),
+                Path::ScriptText => "/scriptText".to_string(),
+                Path::QuietDown => "/quietDown".to_string(),
+                Path::CancelQuietDown => "/cancelQuietDown".to_string(),
+                Path::SafeRestart => "/safeRestart".to_string(),
+                Path::Restart => "/restart".to_string(),
+                Path::SafeExit => "/safeExit".to_string(),
+                Path::Exit => "/exit".to_string(),
+                Path::ComputerScriptText { ref name } => format!("/computer/{name}/scriptText"),
+                Path::ComputerCreateItem => "/computer/doCreateItem".to_string(),
+                Path::ComputerConfigXML { ref name } => format!("/computer/{name}/config.xml"),
+                Path::ComputerDelete { ref name } => format!("/computer/{name}/doDelete"),
+                Path::ComputerToggleOffline { ref name } => {
+                    format!("/computer/{name}/toggleOffline")
+                }
+                Path::ComputerLaunchAgent { ref name } => {
+                    format!("/computer/{name}/launchSlaveAgent")
+                }
+                Path::ComputerDisconnect { ref name } => format!("/computer/{name}/doDisconnect"),
+                Path::ComputerJnlp { ref name } => {
+                    format!("/computer/{name}/jenkins-agent.jnlp")
+                }
+                Path::ComputerLoadStatistics { ref name } => {
+                    format!("/computer/{name}/loadStatistics")
+                }
+                Path::ComputerExecutorStop {
+                    ref name,
+                    number,
+                    one_off,
+                } => format!(
+                    "/computer/{name}/{}/{number}/stop",
+                    if one_off {
+                        "oneOffExecutors"
+                    } else {
+                        "executors"
+                    }
+                ),
+                Path::Label { ref name } => format!("/label/{name}"),
+                Path::LabelLoadStatistics { ref name } => format!("/label/{name}/loadStatistics"),
+                Path::OverallLoad => "/overallLoad".to_string(),
+                Path::BuildByTokenWithParameters => {
+                    "/buildByToken/buildWithParameters".to_string()
+                }
                 Path::PollSCMJob { ref name } => format!("/job/{name}/polling"),
                 Path::JobEnable { ref name } => format!("/job/{name}/enable"),
                 Path::JobDisable { ref name } => format!("/job/{name}/disable"),
@@ -141,6 +320,38 @@ impl<'a> std::fmt::Display for Path<'a> {
                     ref number,
                     configuration: Some(ref configuration),
                 } => format!("/job/{job_name}/{configuration}/{number}"),
+                Path::BuildDelete {
+                    ref job_name,
+                    ref number,
+                    ref configuration,
+                } => format!(
+                    "{}/doDelete",
+                    build_url(job_name, number, configuration.as_ref())
+                ),
+                Path::BuildToggleKeepLog {
+                    ref job_name,
+                    ref number,
+                    ref configuration,
+                } => format!(
+                    "{}/toggleLogKeep",
+                    build_url(job_name, number, configuration.as_ref())
+                ),
+                Path::BuildSubmitDescription {
+                    ref job_name,
+                    ref number,
+                    ref configuration,
+                } => format!(
+                    "{}/submitDescription",
+                    build_url(job_name, number, configuration.as_ref())
+                ),
+                Path::BuildConfigSubmit {
+                    ref job_name,
+                    ref number,
+                    ref configuration,
+                } => format!(
+                    "{}/configSubmit",
+                    build_url(job_name, number, configuration.as_ref())
+                ),
                 Path::ConsoleText {
                     ref job_name,
                     ref number,
@@ -198,11 +409,70 @@ impl<'a> std::fmt::Display for Path<'a> {
                     ref number,
                     configuration: Some(ref configuration),
                     ref relative_path,
-                } => format!("/job/{job_name}/{number}/{configuration}/artifact/{relative_path}"),
+                } => format!("/job/{job_name}/{configuration}/{number}/artifact/{relative_path}"),
+                Path::ArtifactsZip {
+                    ref job_name,
+                    ref number,
+                    ref configuration,
+                } => format!(
+                    "{}/artifact/*zip*/archive.zip",
+                    build_url(job_name, number, configuration.as_ref())
+                ),
                 Path::InFolder {
                     ref folder_name,
                     ref path,
                 } => format!("/job/{folder_name}{path}"),
+                Path::InView {
+                    ref view_name,
+                    ref path,
+                } => format!("/view/{view_name}{path}"),
+                Path::CreateView => "/createView".to_string(),
+                Path::WhoAmI => "/whoAmI".to_string(),
+                Path::User { ref id } => format!("/user/{id}"),
+                Path::AsynchPeople => "/asynchPeople".to_string(),
+                Path::GenerateApiToken { ref user_id } => format!(
+                    "/user/{user_id}/descriptorByName/jenkins.security.ApiTokenProperty/generateNewToken"
+                ),
+                Path::RevokeApiToken { ref user_id } => format!(
+                    "/user/{user_id}/descriptorByName/jenkins.security.ApiTokenProperty/revoke"
+                ),
+                Path::ViewDelete { ref name } => format!("/view/{name}/doDelete"),
+                Path::ViewConfigXML { ref name } => format!("/view/{name}/config.xml"),
+                Path::BluePipelines => "/blue/rest/organizations/jenkins/pipelines/".to_string(),
+                Path::BluePipeline { ref full_name } => format!(
+                    "/blue/rest/organizations/jenkins/pipelines/{}/",
+                    full_name
+                        .decoded()
+                        .split('/')
+                        .map(urlencoding::encode)
+                        .collect::<Vec<_>>()
+                        .join("/pipelines/")
+                ),
+                Path::CascExport => "/configuration-as-code/export".to_string(),
+                Path::CascCheck => "/configuration-as-code/check".to_string(),
+                Path::CascApply => "/configuration-as-code/apply".to_string(),
+                Path::PipelineModelValidate => "/pipeline-model-converter/validate".to_string(),
+                Path::PipelineModelToJson => "/pipeline-model-converter/toJson".to_string(),
+                Path::PipelineModelToJenkinsfile => {
+                    "/pipeline-model-converter/toJenkinsfile".to_string()
+                }
+                Path::PluginManager => "/pluginManager".to_string(),
+                Path::InstallNecessaryPlugins => {
+                    "/pluginManager/installNecessaryPlugins".to_string()
+                }
+                Path::CheckUpdatesServer => "/pluginManager/checkUpdatesServer".to_string(),
+                Path::UpdateCenter => "/updateCenter".to_string(),
+                Path::Credentials { store } => format!("/credentials/store/{store}/domain/_"),
+                Path::CreateCredentials { store } => {
+                    format!("/credentials/store/{store}/domain/_/createCredentials")
+                }
+                Path::CredentialConfigXML { store, ref id } => {
+                    format!("/credentials/store/{store}/domain/_/credential/{id}/config.xml")
+                }
+                Path::CredentialDelete { store, ref id } => {
+                    format!("/credentials/store/{store}/domain/_/credential/{id}/doDelete")
+                }
+                Path::Fingerprint { md5 } => format!("/fingerprint/{md5}"),
                 Path::Computers => "/computer/api/json".to_string(),
                 Path::Computer { ref name } => format!("/computer/{name}/api/json"),
                 Path::Raw { path } => path.to_string(),
@@ -212,6 +482,54 @@ impl<'a> std::fmt::Display for Path<'a> {
     }
 }
 
+fn build_url(job_name: &Name, number: &build::BuildNumber, configuration: Option<&Name>) -> String {
+    match configuration {
+        Some(configuration) => format!("/job/{job_name}/{configuration}/{number}"),
+        None => format!("/job/{job_name}/{number}"),
+    }
+}
+
+impl<'a> Path<'a> {
+    /// Replace the innermost path with the result of `f`, keeping the folders around it
+    pub(crate) fn map_innermost<F>(self, f: F) -> Option<Path<'a>>
+    where
+        F: FnOnce(Path<'a>) -> Option<Path<'a>>,
+    {
+        match self {
+            Path::InFolder { folder_name, path } => {
+                path.map_innermost(f).map(|path| Path::InFolder {
+                    folder_name,
+                    path: Box::new(path),
+                })
+            }
+            Path::InView { view_name, path } => path.map_innermost(f).map(|path| Path::InView {
+                view_name,
+                path: Box::new(path),
+            }),
+            path => f(path),
+        }
+    }
+
+    /// Is this path authenticated by a token, and exempted from CSRF protection
+    pub(crate) fn is_token_authenticated(&self) -> bool {
+        matches!(self, Path::BuildByToken | Path::BuildByTokenWithParameters)
+    }
+
+    /// Full name of a job, including its folders, separated by `/`
+    pub(crate) fn job_full_name(&self) -> Option<String> {
+        match *self {
+            Path::InFolder {
+                ref folder_name,
+                ref path,
+            } => path
+                .job_full_name()
+                .map(|name| format!("{}/{name}", folder_name.decoded())),
+            Path::Job { ref name, .. } => Some(name.decoded()),
+            _ => None,
+        }
+    }
+}
+
 impl Jenkins {
     pub(crate) fn url_to_path<'a>(&self, url: &'a str) -> Path<'a> {
         let path = if url.starts_with(&self.url) {
@@ -225,10 +543,21 @@ impl Jenkins {
             .map(|c| c.0)
             .collect();
 
+        let nested =
+            slashes.len() >= 5 && matches!(&path[slashes[2]..slashes[3]], "/job" | "/view");
+
         match (&path[0..slashes[1]], slashes.len()) {
             ("/view", 3) => Path::View {
                 name: Name::UrlEncodedName(&path[6..(path.len() - 1)]),
             },
+            ("/view", _) if nested => Path::InView {
+                view_name: Name::UrlEncodedName(&path[6..slashes[2]]),
+                path: Box::new(self.url_to_path(&path[slashes[2]..])),
+            },
+            ("/job", _) if nested => Path::InFolder {
+                folder_name: Name::UrlEncodedName(&path[5..slashes[2]]),
+                path: Box::new(self.url_to_path(&path[slashes[2]..])),
+            },
             ("/job", 3) => Path::Job {
                 name: Name::UrlEncodedName(&path[5..(path.len() - 1)]),
                 configuration: None,
@@ -258,11 +587,6 @@ impl Jenkins {
                         ),
                         configuration: None,
                     }
-                } else if &path[slashes[2]..slashes[3]] == "/job" {
-                    Path::InFolder {
-                        folder_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        path: Box::new(self.url_to_path(&path[slashes[2]..])),
-                    }
                 } else {
                     Path::Build {
                         job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
@@ -275,24 +599,19 @@ impl Jenkins {
                     }
                 }
             }
-            ("/job", 6) => {
-                if &path[slashes[2]..slashes[3]] == "/job" {
-                    Path::InFolder {
-                        folder_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        path: Box::new(self.url_to_path(&path[slashes[2]..])),
-                    }
-                } else {
-                    Path::MavenArtifactRecord {
-                        job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        number: build::BuildNumber::Number(
-                            path[(slashes[3] + 1)..slashes[4]].parse().unwrap(),
-                        ),
-                        configuration: Some(Name::UrlEncodedName(
-                            &path[(slashes[2] + 1)..slashes[3]],
-                        )),
-                    }
-                }
-            }
+            ("/job", 6) => Path::MavenArtifactRecord {
+                job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
+                number: build::BuildNumber::Number(
+                    path[(slashes[3] + 1)..slashes[4]].parse().unwrap(),
+                ),
+                configuration: Some(Name::UrlEncodedName(&path[(slashes[2] + 1)..slashes[3]])),
+            },
+            ("/user", 2) => Path::User {
+                id: Name::UrlEncodedName(&path[6..]),
+            },
+            ("/user", 3) => Path::User {
+                id: Name::UrlEncodedName(&path[6..(path.len() - 1)]),
+            },
             ("/queue", 4) => Path::QueueItem {
                 id: path[(slashes[2] + 1)..(path.len() - 1)].parse().unwrap(),
             },
@@ -307,6 +626,35 @@ mod tests {
 
     static JENKINS_URL: &str = "http://none:8080";
 
+    #[test]
+    fn can_parse_nested_view_paths() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client.url_to_path("/view/parent/view/child/");
+        assert_eq!(
+            path,
+            Path::InView {
+                view_name: Name::UrlEncodedName("parent"),
+                path: Box::new(Path::View {
+                    name: Name::UrlEncodedName("child")
+                }),
+            }
+        );
+        assert_eq!(path.to_string(), "/view/parent/view/child");
+
+        let path = jenkins_client.url_to_path("/job/folder/view/myview/");
+        assert_eq!(
+            path,
+            Path::InFolder {
+                folder_name: Name::UrlEncodedName("folder"),
+                path: Box::new(Path::View {
+                    name: Name::UrlEncodedName("myview")
+                }),
+            }
+        );
+        assert_eq!(path.to_string(), "/job/folder/view/myview");
+    }
+
     #[test]
     fn can_parse_view_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
@@ -378,6 +726,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_map_build_path_in_folder() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client
+            .url_to_path("/job/myfolder/job/myjob/1/")
+            .map_innermost(|path| match path {
+                Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                } => Some(Path::BuildDelete {
+                    job_name,
+                    number,
+                    configuration,
+                }),
+                _ => None,
+            });
+        assert_eq!(
+            path.map(|path| path.to_string()),
+            Some("/job/myfolder/job/myjob/1/doDelete".to_string())
+        );
+    }
+
     #[test]
     fn can_parse_unknown_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
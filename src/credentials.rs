@@ -0,0 +1,375 @@
+//! Jenkins Credentials, from the Credentials plugin
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client::Result;
+use crate::client_internals::{Name, Path, SecretBody, check_permission};
+use crate::helpers::escape_xml;
+use crate::secret::{Secret, SecretBytes};
+
+/// A credentials store. Only the global domain of the store is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsStore<'a> {
+    /// The system store of Jenkins
+    System,
+    /// The store of a folder, identified by its full name (`parent/folder`)
+    Folder(&'a str),
+}
+impl<'a> CredentialsStore<'a> {
+    fn store_name(&self) -> &'static str {
+        match self {
+            CredentialsStore::System => "system",
+            CredentialsStore::Folder(_) => "folder",
+        }
+    }
+
+    fn path(&self, path: Path<'a>) -> Path<'a> {
+        match *self {
+            CredentialsStore::System => path,
+            CredentialsStore::Folder(full_name) => {
+                full_name
+                    .rsplit('/')
+                    .fold(path, |path, folder_name| Path::InFolder {
+                        folder_name: Name::Name(folder_name),
+                        path: Box::new(path),
+                    })
+            }
+        }
+    }
+}
+
+/// Fingerprint of a credential
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialFingerprint {
+    /// MD5 hash of the credential
+    pub hash: String,
+}
+
+/// A credential, as listed by Jenkins. Secret values are never provided
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    /// ID of the credential, used to reference it
+    pub id: String,
+    /// Name of the type of credential
+    pub type_name: String,
+    /// Display name of the credential
+    pub display_name: Option<String>,
+    /// Description of the credential
+    pub description: Option<String>,
+    /// Fingerprint of the credential, if it was used
+    pub fingerprint: Option<CredentialFingerprint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CredentialsDomain {
+    credentials: Vec<CredentialInfo>,
+}
+
+/// Scope of a credential
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CredentialScope {
+    /// Available to jobs and to Jenkins
+    #[default]
+    Global,
+    /// Only available to Jenkins itself, for example to connect to agents
+    System,
+}
+
+/// Kind of credential, with its values
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialKind {
+    /// A username with a password
+    UsernamePassword {
+        /// Username
+        username: String,
+        /// Password
+        password: Secret,
+    },
+    /// A secret text. Requires the `plain-credentials` plugin
+    SecretText {
+        /// Secret text
+        secret: Secret,
+    },
+    /// A SSH username with a private key. Requires the `ssh-credentials` plugin
+    SshPrivateKey {
+        /// Username
+        username: String,
+        /// Private key
+        private_key: Secret,
+        /// Passphrase of the private key
+        passphrase: Option<Secret>,
+    },
+    /// A secret file. Requires the `plain-credentials` plugin
+    SecretFile {
+        /// Name of the file
+        file_name: String,
+        /// Content of the file
+        content: SecretBytes,
+    },
+}
+impl CredentialKind {
+    fn class(&self) -> &'static str {
+        match self {
+            CredentialKind::UsernamePassword { .. } => {
+                "com.cloudbees.plugins.credentials.impl.UsernamePasswordCredentialsImpl"
+            }
+            CredentialKind::SecretText { .. } => {
+                "org.jenkinsci.plugins.plaincredentials.impl.StringCredentialsImpl"
+            }
+            CredentialKind::SshPrivateKey { .. } => {
+                "com.cloudbees.jenkins.plugins.sshcredentials.impl.BasicSSHUserPrivateKey"
+            }
+            CredentialKind::SecretFile { .. } => {
+                "org.jenkinsci.plugins.plaincredentials.impl.FileCredentialsImpl"
+            }
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        match self {
+            CredentialKind::UsernamePassword { username, password } => format!(
+                "<username>{}</username><password>{}</password>",
+                escape_xml(username),
+                escape_xml(password.expose())
+            ),
+            CredentialKind::SecretText { secret } => {
+                format!("<secret>{}</secret>", escape_xml(secret.expose()))
+            }
+            CredentialKind::SshPrivateKey {
+                username,
+                private_key,
+                passphrase,
+            } => format!(
+                "<username>{}</username>\
+                <privateKeySource class=\"{}$DirectEntryPrivateKeySource\">\
+                <privateKey>{}</privateKey></privateKeySource>\
+                <passphrase>{}</passphrase>",
+                escape_xml(username),
+                self.class(),
+                escape_xml(private_key.expose()),
+                passphrase
+                    .as_ref()
+                    .map(|passphrase| escape_xml(passphrase.expose()))
+                    .unwrap_or_default()
+            ),
+            CredentialKind::SecretFile { file_name, content } => format!(
+                "<fileName>{}</fileName><secretBytes>{}</secretBytes>",
+                escape_xml(file_name),
+                base64::engine::general_purpose::STANDARD.encode(content.expose())
+            ),
+        }
+    }
+}
+
+/// A credential to create or update
+#[derive(Debug, Clone, PartialEq)]
+pub struct Credential {
+    /// ID of the credential
+    pub id: String,
+    /// Description of the credential
+    pub description: Option<String>,
+    /// Scope of the credential
+    pub scope: CredentialScope,
+    /// Kind of credential
+    pub kind: CredentialKind,
+}
+impl Credential {
+    /// Create a credential with a global scope
+    pub fn new(id: &str, kind: CredentialKind) -> Self {
+        Credential {
+            id: id.to_string(),
+            description: None,
+            scope: CredentialScope::default(),
+            kind,
+        }
+    }
+
+    /// Set the description of the credential
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Set the scope of the credential
+    pub fn with_scope(mut self, scope: CredentialScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Build the config.xml of the credential
+    pub fn to_config_xml(&self) -> String {
+        let class = self.kind.class();
+        format!(
+            "<{class}><scope>{}</scope><id>{}</id><description>{}</description>{}</{class}>",
+            match self.scope {
+                CredentialScope::Global => "GLOBAL",
+                CredentialScope::System => "SYSTEM",
+            },
+            escape_xml(&self.id),
+            self.description
+                .as_deref()
+                .map(escape_xml)
+                .unwrap_or_default(),
+            self.kind.to_xml(),
+        )
+    }
+}
+
+impl Jenkins {
+    /// List the credentials of a store
+    pub async fn list_credentials(
+        &self,
+        store: CredentialsStore<'_>,
+    ) -> Result<Vec<CredentialInfo>> {
+        let path = store.path(Path::Credentials {
+            store: store.store_name(),
+        });
+        let domain: CredentialsDomain = check_permission(
+            self.get_with_params(
+                &path,
+                [(
+                    "tree",
+                    "credentials[id,typeName,displayName,description,fingerprint[hash]]",
+                )],
+            )
+            .await,
+            "Credentials/View",
+        )?
+        .json()
+        .await?;
+        Ok(domain.credentials)
+    }
+
+    /// Create a credential in a store
+    pub async fn create_credential(
+        &self,
+        store: CredentialsStore<'_>,
+        credential: &Credential,
+    ) -> Result<()> {
+        let path = store.path(Path::CreateCredentials {
+            store: store.store_name(),
+        });
+        let _ = check_permission(
+            self.post_xml(&path, SecretBody(credential.to_config_xml()), &[])
+                .await,
+            "Credentials/Create",
+        )?;
+        Ok(())
+    }
+
+    /// Update the credential with the same ID in a store
+    pub async fn update_credential(
+        &self,
+        store: CredentialsStore<'_>,
+        credential: &Credential,
+    ) -> Result<()> {
+        let path = store.path(Path::CredentialConfigXML {
+            store: store.store_name(),
+            id: Name::Name(&credential.id),
+        });
+        let _ = check_permission(
+            self.post_xml(&path, SecretBody(credential.to_config_xml()), &[])
+                .await,
+            "Credentials/Update",
+        )?;
+        Ok(())
+    }
+
+    /// Delete the credential `id` from a store
+    pub async fn delete_credential(&self, store: CredentialsStore<'_>, id: &str) -> Result<()> {
+        let path = store.path(Path::CredentialDelete {
+            store: store.store_name(),
+            id: Name::Name(id),
+        });
+        let _ = check_permission(self.post(&path).await, "Credentials/Delete")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_list_folder_credentials() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock(
+                "GET",
+                "/job/parent/job/team/credentials/store/folder/domain/_/api/json",
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"{"credentials": [{
+                    "id": "deploy",
+                    "typeName": "Username with password",
+                    "displayName": "deploy/******",
+                    "description": null,
+                    "fingerprint": {"hash": "4d9a6f5c2e1b0a3d7c8e9f0a1b2c3d4e"}
+                }]}"#,
+            )
+            .create();
+
+        let credentials = jenkins_client
+            .list_credentials(CredentialsStore::Folder("parent/team"))
+            .await
+            .unwrap();
+
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(credentials[0].id, "deploy");
+        assert_eq!(
+            credentials[0].fingerprint.as_ref().unwrap().hash,
+            "4d9a6f5c2e1b0a3d7c8e9f0a1b2c3d4e"
+        );
+    }
+
+    #[tokio::test]
+    async fn can_create_credential() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock(
+                "POST",
+                "/credentials/store/system/domain/_/createCredentials",
+            )
+            .match_header("content-type", "application/xml")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("<id>kubeconfig</id>".to_string()),
+                mockito::Matcher::Regex("<fileName>config</fileName>".to_string()),
+                mockito::Matcher::Regex(
+                    "<secretBytes>YXBpVmVyc2lvbjogdjE=</secretBytes>".to_string(),
+                ),
+            ]))
+            .create();
+
+        let credential = Credential::new(
+            "kubeconfig",
+            CredentialKind::SecretFile {
+                file_name: "config".to_string(),
+                content: SecretBytes::new("apiVersion: v1"),
+            },
+        );
+        assert!(!format!("{:?}", credential).contains("apiVersion"));
+
+        jenkins_client
+            .create_credential(CredentialsStore::System, &credential)
+            .await
+            .unwrap();
+        mock.assert();
+    }
+}
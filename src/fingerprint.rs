@@ -0,0 +1,150 @@
+//! Fingerprints of the files tracked by Jenkins, to know which builds produced and used them
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client::{self, Result};
+use crate::client_internals::Path;
+
+/// A build referenced by a `Fingerprint`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintBuild {
+    /// Full name of the job
+    pub name: String,
+    /// Build number
+    pub number: u32,
+}
+
+/// A range of build numbers, `end` excluded
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRange {
+    /// First build number of the range
+    pub start: u32,
+    /// Build number after the last build of the range
+    pub end: u32,
+}
+
+/// A set of ranges of build numbers
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRangeSet {
+    /// Ranges of build numbers
+    pub ranges: Vec<BuildRange>,
+}
+
+/// Builds of a job that used a fingerprinted file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintUsage {
+    /// Full name of the job
+    pub name: String,
+    /// Build numbers that used the file
+    pub ranges: BuildRangeSet,
+}
+impl FingerprintUsage {
+    /// List the build numbers that used the file
+    pub fn build_numbers(&self) -> impl Iterator<Item = u32> + '_ {
+        self.ranges
+            .ranges
+            .iter()
+            .flat_map(|range| range.start..range.end)
+    }
+}
+
+/// Fingerprint of a file, with the build that produced it and the builds that used it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    /// Name of the file
+    pub file_name: String,
+    /// MD5 checksum of the file
+    pub hash: String,
+    /// Build that produced the file, if it was produced by Jenkins
+    pub original: Option<FingerprintBuild>,
+    /// Timestamp of the first time the file was seen
+    pub timestamp: u64,
+    /// Builds that used the file, by job
+    pub usage: Vec<FingerprintUsage>,
+}
+
+impl Jenkins {
+    /// Get a `Fingerprint` from the MD5 checksum of a file
+    ///
+    /// # Errors
+    /// If `md5` is not 32 hexadecimal digits, this will return an
+    /// [`Error::InvalidFingerprint`](../client/enum.Error.html#variant.InvalidFingerprint)
+    pub async fn get_fingerprint(&self, md5: &str) -> Result<Fingerprint> {
+        if md5.len() != 32 || !md5.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(client::Error::InvalidFingerprint {
+                md5: md5.to_string(),
+            }
+            .into());
+        }
+        Ok(self.get(&Path::Fingerprint { md5 }).await?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static FINGERPRINT: &str = r#"{
+        "fileName": "app.jar",
+        "hash": "0123456789abcdef0123456789abcdef",
+        "original": {"name": "folder/app", "number": 3},
+        "timestamp": 1700000000000,
+        "usage": [
+            {"name": "folder/app", "ranges": {"ranges": [{"start": 3, "end": 4}]}},
+            {"name": "deploy", "ranges": {"ranges": [{"start": 10, "end": 12}]}}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn can_get_fingerprint() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let _ = s
+            .mock(
+                "GET",
+                "/fingerprint/0123456789abcdef0123456789abcdef/api/json",
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body(FINGERPRINT)
+            .create();
+
+        let fingerprint = jenkins_client
+            .get_fingerprint("0123456789abcdef0123456789abcdef")
+            .await
+            .unwrap();
+
+        assert_eq!(fingerprint.file_name, "app.jar");
+        assert_eq!(fingerprint.original.unwrap().number, 3);
+        assert_eq!(
+            fingerprint.usage[1].build_numbers().collect::<Vec<_>>(),
+            vec![10, 11]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_fingerprint_is_rejected() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://localhost:1")
+            .build()
+            .unwrap();
+
+        for md5 in [
+            "../../script",
+            "0123456789abcdef",
+            "0123456789abcdef0123456789abcdeg",
+        ] {
+            let error = jenkins_client.get_fingerprint(md5).await.unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<client::Error>(),
+                Some(client::Error::InvalidFingerprint { .. })
+            ));
+        }
+    }
+}
@@ -6,6 +6,26 @@ pub trait Class {
     fn with_class() -> &'static str;
 }
 
+/// Escape a value to be included in XML
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Unescape a value read from XML
+pub(crate) fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
 macro_rules! register_class {
     ($class:expr => $variant:ty) => {
         impl Class for $variant {
@@ -1,9 +1,12 @@
 //! Jenkins Home, describing state of the master
 
+use std::time::{Duration, Instant};
+
 use serde::Deserialize;
 
 use crate::Jenkins;
-use crate::client_internals::{Path, Result};
+use crate::client;
+use crate::client_internals::{Path, Result, check_permission};
 use crate::job::ShortJob;
 use crate::view::ShortView;
 
@@ -144,9 +147,398 @@ pub struct Home {
     pub views: Vec<ShortView>,
 }
 
+/// Delay between two checks when waiting for Jenkins
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Time after which Jenkins answering without quieting down is considered restarted, when it
+/// was never seen down
+const RESTART_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Header with the ID of the current session of Jenkins, that changes when it restarts
+const SESSION_HEADER: &str = "X-Jenkins-Session";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuietingDown {
+    quieting_down: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BusyExecutors {
+    busy_executors: u32,
+}
+
+/// Was the connection closed by Jenkins after the request was sent
+fn is_connection_reset(error: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(error);
+    while let Some(err) = source {
+        if let Some(io_error) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io_error.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Jenkins closes connections or replies `503 Service Unavailable` while restarting, which
+/// means the request was accepted. Failing to connect means it was not sent
+fn accept_restarting<T>(result: Result<T>) -> Result<()> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => match err.downcast_ref::<reqwest::Error>() {
+            Some(error)
+                if error.status() == Some(reqwest::StatusCode::SERVICE_UNAVAILABLE)
+                    || (!error.is_connect() && is_connection_reset(error)) =>
+            {
+                Ok(())
+            }
+            _ => Err(err),
+        },
+    }
+}
+
+/// Is Jenkins down or restarting, from the error of a request
+fn is_down(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(error) => {
+            error.status() == Some(reqwest::StatusCode::SERVICE_UNAVAILABLE)
+                || error.is_connect()
+                || is_connection_reset(error)
+        }
+        None => false,
+    }
+}
+
 impl Jenkins {
     /// Get Jenkins `Home`
     pub async fn get_home(&self) -> Result<Home> {
         Ok(self.get(&Path::Home).await?.json().await?)
     }
+
+    /// Run a Groovy script with the script console of the master, returning what it printed
+    ///
+    /// # Errors
+    /// If the script throws an exception, this will return an
+    /// [`Error::ScriptException`](../client/enum.Error.html#variant.ScriptException)
+    pub async fn run_script(&self, script: &str) -> Result<String> {
+        self.run_script_at(&Path::ScriptText, script).await
+    }
+
+    /// Prepare Jenkins for shutdown: no new builds will be started. The `reason` is displayed
+    /// to users
+    pub async fn quiet_down(&self, reason: Option<&str>) -> Result<()> {
+        let qps: Vec<(&str, &str)> = reason.into_iter().map(|r| ("reason", r)).collect();
+        let _ = check_permission(
+            self.post_with_body(&Path::QuietDown, "", &qps).await,
+            "Overall/Administer",
+        )?;
+        Ok(())
+    }
+
+    /// Cancel the effect of `quiet_down`
+    pub async fn cancel_quiet_down(&self) -> Result<()> {
+        let _ = check_permission(
+            self.post(&Path::CancelQuietDown).await,
+            "Overall/Administer",
+        )?;
+        Ok(())
+    }
+
+    /// Restart Jenkins once no builds are running
+    pub async fn safe_restart(&self) -> Result<()> {
+        check_permission(
+            accept_restarting(self.post(&Path::SafeRestart).await),
+            "Overall/Administer",
+        )
+    }
+
+    /// Restart Jenkins immediately, aborting running builds
+    pub async fn restart(&self) -> Result<()> {
+        check_permission(
+            accept_restarting(self.post(&Path::Restart).await),
+            "Overall/Administer",
+        )
+    }
+
+    /// Shut down Jenkins once no builds are running
+    pub async fn safe_exit(&self) -> Result<()> {
+        check_permission(
+            accept_restarting(self.post(&Path::SafeExit).await),
+            "Overall/Administer",
+        )
+    }
+
+    /// Shut down Jenkins immediately, aborting running builds
+    pub async fn exit(&self) -> Result<()> {
+        check_permission(
+            accept_restarting(self.post(&Path::Exit).await),
+            "Overall/Administer",
+        )
+    }
+
+    /// Wait until Jenkins is quieting down and no executors are busy, to drain it before a
+    /// maintenance. Call `quiet_down` first, or this will wait until someone else does
+    ///
+    /// # Errors
+    /// If Jenkins is not idle after `timeout`, this will return an
+    /// [`Error::Timeout`](../client/enum.Error.html#variant.Timeout)
+    pub async fn wait_until_idle(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let home: QuietingDown = self
+                .get_with_params(&Path::Home, [("tree", "quietingDown")])
+                .await?
+                .json()
+                .await?;
+            if home.quieting_down {
+                let computers: BusyExecutors = self
+                    .get_with_params(&Path::Computers, [("tree", "busyExecutors")])
+                    .await?
+                    .json()
+                    .await?;
+                if computers.busy_executors == 0 {
+                    return Ok(());
+                }
+            }
+            wait_before_retry(deadline, "Jenkins to be idle").await?;
+        }
+    }
+
+    /// Wait until Jenkins restarts, after `restart` or `safe_restart`, and answers its API
+    /// again. Jenkins is restarted once it was seen down, once its session changed, or if it
+    /// answers without quieting down for a few seconds, in case the restart was too fast to
+    /// be seen. Errors while Jenkins is unreachable or starting are ignored
+    ///
+    /// # Errors
+    /// If Jenkins is not up again after `timeout`, this will return an
+    /// [`Error::Timeout`](../client/enum.Error.html#variant.Timeout)
+    pub async fn wait_until_up(&self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        let deadline = start + timeout;
+        let mut seen_down = false;
+        let mut first_session: Option<Option<String>> = None;
+        loop {
+            match self
+                .get_with_params(&Path::Home, [("tree", "quietingDown")])
+                .await
+            {
+                Ok(response) => {
+                    let session = response
+                        .headers()
+                        .get(SESSION_HEADER)
+                        .and_then(|session| session.to_str().ok())
+                        .map(ToString::to_string);
+                    match response.json::<QuietingDown>().await {
+                        Ok(home) => {
+                            if seen_down
+                                || (!home.quieting_down && start.elapsed() >= RESTART_GRACE_PERIOD)
+                            {
+                                return Ok(());
+                            }
+                            match &first_session {
+                                None => first_session = Some(session),
+                                Some(first) if session.is_some() && session != *first => {
+                                    return Ok(());
+                                }
+                                Some(_) => (),
+                            }
+                        }
+                        // not the API of Jenkins, like a page of a proxy while it is down
+                        Err(_) => seen_down = true,
+                    }
+                }
+                Err(err) if is_down(err.as_ref()) => seen_down = true,
+                Err(err) => return Err(err),
+            }
+            wait_before_retry(deadline, "Jenkins to restart").await?;
+        }
+    }
+}
+
+/// Sleep before checking again, or fail if there is no time left
+async fn wait_before_retry(deadline: Instant, operation: &'static str) -> Result<()> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return Err(client::Error::Timeout { operation }.into());
+    }
+    tokio::time::sleep(remaining.min(POLL_INTERVAL)).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_wait_until_idle() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"quietingDown": true}"#)
+            .create();
+        let busy = s
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/computer/api/json".to_string()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"busyExecutors": 1}"#)
+            .expect(1)
+            .create();
+        let _ = s
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/computer/api/json".to_string()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"busyExecutors": 0}"#)
+            .create();
+
+        jenkins_client
+            .wait_until_idle(Duration::from_secs(10))
+            .await
+            .unwrap();
+        busy.assert();
+    }
+
+    #[tokio::test]
+    async fn can_wait_until_restarted() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let before_restart = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"quietingDown": true}"#)
+            .expect(1)
+            .create();
+        let restarting = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(1)
+            .create();
+        let restarted = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"quietingDown": false}"#)
+            .create();
+
+        jenkins_client
+            .wait_until_up(Duration::from_secs(10))
+            .await
+            .unwrap();
+        before_restart.assert();
+        restarting.assert();
+        restarted.assert();
+    }
+
+    #[tokio::test]
+    async fn can_wait_until_restarted_with_new_session() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let before_restart = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins-Session", "0a1b2c3d")
+            .with_body(r#"{"quietingDown": true}"#)
+            .expect(1)
+            .create();
+        let restarted = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins-Session", "4e5f6a7b")
+            .with_body(r#"{"quietingDown": false}"#)
+            .expect(1)
+            .create();
+
+        jenkins_client
+            .wait_until_up(Duration::from_secs(10))
+            .await
+            .unwrap();
+        before_restart.assert();
+        restarted.assert();
+    }
+
+    #[tokio::test]
+    async fn can_wait_until_up_when_already_restarted() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins-Session", "4e5f6a7b")
+            .with_body(r#"{"quietingDown": false}"#)
+            .create();
+
+        jenkins_client
+            .wait_until_up(Duration::from_secs(10))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn restart_is_not_sent_when_jenkins_is_unreachable() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://127.0.0.1:1")
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.restart().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn wait_until_up_times_out() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .create();
+
+        let result = jenkins_client
+            .wait_until_up(Duration::from_millis(100))
+            .await;
+
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<client::Error>(),
+            Some(client::Error::Timeout { .. })
+        ));
+    }
 }
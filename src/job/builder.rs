@@ -1,24 +1,344 @@
 //! Helper to build a job
 
+use bytes::Bytes;
+use futures_util::TryStream;
+use reqwest::Body;
 use reqwest::header::LOCATION;
+use reqwest::multipart::{Form, Part};
 
 use serde::{self, Serialize};
 
 use crate::Jenkins;
 use crate::client::{self, Result};
-use crate::client_internals::{Name, Path};
+use crate::client_internals::error::ParameterIssue;
+use crate::client_internals::{Name, Path, SecretBody};
+use crate::helpers::Class;
 use crate::job::{Job, JobName};
+use crate::property::parameters::{
+    BooleanParameterDefinition, ChoiceParameterDefinition, CommonParameterDefinition,
+    ExtendedChoiceParameterDefinition, FileParameterDefinition, GitParameterDefinition,
+    PasswordParameterDefinition, TextParameterDefinition,
+};
 use crate::queue::ShortQueueItem;
 
+/// Value of a build parameter
+#[derive(Clone, PartialEq)]
+pub enum ParameterValue {
+    /// A string, accepted by any parameter except files
+    String(String),
+    /// A boolean
+    Bool(bool),
+    /// One of the choices of a choice parameter
+    Choice(String),
+    /// A multi-line string
+    Text(String),
+    /// A password
+    Password(String),
+}
+impl ParameterValue {
+    fn as_form_value(&self) -> String {
+        match *self {
+            ParameterValue::Bool(value) => value.to_string(),
+            ParameterValue::String(ref value)
+            | ParameterValue::Choice(ref value)
+            | ParameterValue::Text(ref value)
+            | ParameterValue::Password(ref value) => value.clone(),
+        }
+    }
+}
+impl std::fmt::Debug for ParameterValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ParameterValue::String(ref value) => f.debug_tuple("String").field(value).finish(),
+            ParameterValue::Bool(value) => f.debug_tuple("Bool").field(&value).finish(),
+            ParameterValue::Choice(ref value) => f.debug_tuple("Choice").field(value).finish(),
+            ParameterValue::Text(ref value) => f.debug_tuple("Text").field(value).finish(),
+            ParameterValue::Password(_) => f.debug_tuple("Password").field(&"***").finish(),
+        }
+    }
+}
+
+/// Parameters of a build, with typed values
+///
+/// ```rust
+/// let parameters = jenkins_api::job::builder::BuildParameters::new()
+///     .with_bool("bool-param", true)
+///     .with_choice("choose between", "value2")
+///     .with_string("free string param", "my string param");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BuildParameters {
+    values: Vec<(String, ParameterValue)>,
+}
+impl BuildParameters {
+    /// Create an empty set of parameters
+    pub fn new() -> Self {
+        BuildParameters { values: vec![] }
+    }
+
+    /// Set the value of a parameter
+    pub fn with_value(mut self, name: &str, value: ParameterValue) -> Self {
+        self.values.retain(|(existing, _)| existing != name);
+        self.values.push((name.to_string(), value));
+        self
+    }
+
+    /// Add a value to a parameter, keeping its previous values
+    fn append(mut self, name: &str, value: ParameterValue) -> Self {
+        self.values.push((name.to_string(), value));
+        self
+    }
+
+    /// Parameters with string values, from a struct or a list of pairs serialized as a form
+    fn from_serializable<T: Serialize>(parameters: &T) -> Result<Self> {
+        let values: Vec<(String, String)> =
+            serde_urlencoded::from_str(&serde_urlencoded::to_string(parameters)?)?;
+        Ok(values
+            .iter()
+            .fold(BuildParameters::new(), |parameters, (name, value)| {
+                parameters.append(name, ParameterValue::String(value.clone()))
+            }))
+    }
+
+    /// Set the value of a string parameter
+    pub fn with_string(self, name: &str, value: &str) -> Self {
+        self.with_value(name, ParameterValue::String(value.to_string()))
+    }
+
+    /// Set the value of a boolean parameter
+    pub fn with_bool(self, name: &str, value: bool) -> Self {
+        self.with_value(name, ParameterValue::Bool(value))
+    }
+
+    /// Set the value of a choice parameter
+    pub fn with_choice(self, name: &str, value: &str) -> Self {
+        self.with_value(name, ParameterValue::Choice(value.to_string()))
+    }
+
+    /// Set the value of a multi-line string parameter
+    pub fn with_text(self, name: &str, value: &str) -> Self {
+        self.with_value(name, ParameterValue::Text(value.to_string()))
+    }
+
+    /// Set the value of a password parameter
+    pub fn with_password(self, name: &str, value: &str) -> Self {
+        self.with_value(name, ParameterValue::Password(value.to_string()))
+    }
+
+    /// Get the value of a parameter
+    pub fn get(&self, name: &str) -> Option<&ParameterValue> {
+        self.values
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Check the parameters against the parameters definitions of a job. Returns the list of
+    /// issues found, empty if the parameters are valid
+    pub fn validate(&self, definitions: &[CommonParameterDefinition]) -> Vec<ParameterIssue> {
+        self.validate_with_files(definitions, &[])
+    }
+
+    fn validate_with_files(
+        &self,
+        definitions: &[CommonParameterDefinition],
+        files: &[&str],
+    ) -> Vec<ParameterIssue> {
+        let mut issues = vec![];
+        for (name, value) in &self.values {
+            match definitions
+                .iter()
+                .find(|definition| &definition.name == name)
+            {
+                None => issues.push(ParameterIssue::UnknownParameter { name: name.clone() }),
+                Some(definition) => issues.extend(Self::validate_value(definition, value)),
+            }
+        }
+        for name in files {
+            match definitions
+                .iter()
+                .find(|definition| &definition.name == name)
+            {
+                None => issues.push(ParameterIssue::UnknownParameter {
+                    name: name.to_string(),
+                }),
+                Some(definition)
+                    if definition.class.as_deref()
+                        != Some(FileParameterDefinition::with_class()) =>
+                {
+                    issues.push(ParameterIssue::InvalidType {
+                        name: name.to_string(),
+                        expected: definition
+                            .parameter_type
+                            .clone()
+                            .or_else(|| definition.class.clone())
+                            .unwrap_or_default(),
+                    })
+                }
+                Some(_) => (),
+            }
+        }
+        for definition in definitions {
+            if definition.default_parameter_value.is_none()
+                && self.get(&definition.name).is_none()
+                && !files.contains(&definition.name.as_str())
+            {
+                issues.push(ParameterIssue::MissingValue {
+                    name: definition.name.clone(),
+                });
+            }
+        }
+        issues
+    }
+
+    fn validate_value(
+        definition: &CommonParameterDefinition,
+        value: &ParameterValue,
+    ) -> Option<ParameterIssue> {
+        let class = definition.class.as_deref().unwrap_or_default();
+        let type_matches = match *value {
+            ParameterValue::String(_) => class != FileParameterDefinition::with_class(),
+            ParameterValue::Bool(_) => class == BooleanParameterDefinition::with_class(),
+            ParameterValue::Choice(_) => [
+                ChoiceParameterDefinition::with_class(),
+                ExtendedChoiceParameterDefinition::with_class(),
+                GitParameterDefinition::with_class(),
+            ]
+            .contains(&class),
+            ParameterValue::Text(_) => class == TextParameterDefinition::with_class(),
+            ParameterValue::Password(_) => class == PasswordParameterDefinition::with_class(),
+        };
+        if !type_matches {
+            return Some(ParameterIssue::InvalidType {
+                name: definition.name.clone(),
+                expected: definition
+                    .parameter_type
+                    .clone()
+                    .unwrap_or_else(|| class.to_string()),
+            });
+        }
+
+        let value = value.as_form_value();
+        let (choices, selected): (Vec<String>, Vec<&str>) = if let Ok(choice) =
+            definition.as_variant::<ChoiceParameterDefinition>()
+        {
+            (choice.choices, vec![value.as_str()])
+        } else if let Ok(choice) = definition.as_variant::<ExtendedChoiceParameterDefinition>() {
+            let delimiter = choice.multi_select_delimiter.as_deref().unwrap_or(",");
+            (
+                choice.choices().into_iter().map(str::to_string).collect(),
+                value.split(delimiter).map(str::trim).collect(),
+            )
+        } else {
+            return None;
+        };
+        if selected
+            .iter()
+            .all(|selected| choices.iter().any(|choice| choice == selected))
+        {
+            None
+        } else {
+            Some(ParameterIssue::InvalidChoice {
+                name: definition.name.clone(),
+                value,
+                choices,
+            })
+        }
+    }
+
+    fn has_password(&self) -> bool {
+        self.values
+            .iter()
+            .any(|(_, value)| matches!(value, ParameterValue::Password(_)))
+    }
+
+    fn to_multipart(&self) -> Form {
+        self.values.iter().fold(Form::new(), |form, (name, value)| {
+            form.text(name.clone(), value.as_form_value())
+        })
+    }
+
+    fn to_form(&self) -> Result<String> {
+        Ok(serde_urlencoded::to_string(
+            self.values
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_form_value()))
+                .collect::<Vec<_>>(),
+        )?)
+    }
+}
+
+/// Content of a file parameter, either in memory or streamed
+#[derive(Debug)]
+pub struct FileContent {
+    body: Body,
+    length: Option<u64>,
+}
+impl FileContent {
+    /// Content streamed from `stream`, with its length if known
+    pub fn from_stream<S>(stream: S, length: Option<u64>) -> Self
+    where
+        S: TryStream + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        FileContent {
+            body: Body::wrap_stream(stream),
+            length,
+        }
+    }
+
+    fn into_part(self) -> Part {
+        match self.length {
+            Some(length) => Part::stream_with_length(self.body, length),
+            None => Part::stream(self.body),
+        }
+    }
+}
+impl From<Bytes> for FileContent {
+    fn from(content: Bytes) -> Self {
+        FileContent {
+            length: Some(content.len() as u64),
+            body: Body::from(content),
+        }
+    }
+}
+impl From<Vec<u8>> for FileContent {
+    fn from(content: Vec<u8>) -> Self {
+        FileContent::from(Bytes::from(content))
+    }
+}
+impl From<&'static [u8]> for FileContent {
+    fn from(content: &'static [u8]) -> Self {
+        FileContent::from(Bytes::from_static(content))
+    }
+}
+impl From<String> for FileContent {
+    fn from(content: String) -> Self {
+        FileContent::from(Bytes::from(content))
+    }
+}
+
+#[derive(Debug)]
+struct FileParameter {
+    name: String,
+    file_name: String,
+    content: FileContent,
+}
+
 /// Helper to build a job
 #[derive(Debug)]
 pub struct JobBuilder<'a, 'b, 'c, 'd> {
-    job_name: Name<'a>,
+    path: Path<'a>,
     jenkins_client: &'b Jenkins,
     delay: Option<u32>,
     cause: Option<&'c str>,
     token: Option<&'d str>,
-    parameters: Option<String>,
+    build_token_root: bool,
+    parameters: Option<BuildParameters>,
+    parameters_error: Option<Box<dyn std::error::Error + Send + Sync>>,
+    files: Vec<FileParameter>,
+    validate: bool,
 }
 
 impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
@@ -27,132 +347,156 @@ impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
     where
         T: Job,
     {
-        let path = jenkins_client.url_to_path(job.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
-            return Ok(JobBuilder {
-                job_name: name,
-                jenkins_client,
-                delay: None,
-                cause: None,
-                token: None,
-                parameters: None,
-            });
-        } else if let Path::InFolder {
-            folder_name: _folder_name,
-            path: sub_path,
-        } = path
-            && let Path::Job {
-                name,
-                configuration: None,
-            } = sub_path.as_ref()
-        {
-            return Ok(JobBuilder {
-                job_name: name.clone(),
-                jenkins_client,
-                delay: None,
-                cause: None,
-                token: None,
-                parameters: None,
-            });
-        }
-        Err(client::Error::InvalidUrl {
-            url: job.url().to_string(),
-            expected: client::error::ExpectedType::Job,
-        }
-        .into())
+        let path = jenkins_client
+            .url_to_path(job.url())
+            .map_innermost(|path| match path {
+                path @ Path::Job {
+                    configuration: None,
+                    ..
+                } => Some(path),
+                _ => None,
+            })
+            .ok_or_else(|| client::Error::InvalidUrl {
+                url: job.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })?;
+        Ok(Self::with_path(path, jenkins_client))
     }
 
     pub(crate) fn new_from_job_name<J>(name: J, jenkins_client: &'b Jenkins) -> Result<Self>
     where
         J: Into<JobName<'a>>,
     {
-        Ok(JobBuilder {
-            job_name: Name::Name(name.into().0),
+        Ok(Self::with_path(
+            Path::Job {
+                name: Name::Name(name.into().0),
+                configuration: None,
+            },
+            jenkins_client,
+        ))
+    }
+
+    fn with_path(path: Path<'a>, jenkins_client: &'b Jenkins) -> Self {
+        JobBuilder {
+            path,
             jenkins_client,
             delay: None,
             cause: None,
             token: None,
+            build_token_root: false,
             parameters: None,
-        })
+            parameters_error: None,
+            files: vec![],
+            validate: false,
+        }
     }
 
     /// Trigger the build
-    pub async fn send(self) -> Result<ShortQueueItem> {
-        let response = match (self.token, self.parameters) {
-            (Some(token), None) => {
-                let bound_cause = self.cause.unwrap_or("");
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                qps.push(("token", token));
-                if self.cause.is_some() {
-                    qps.push(("cause", bound_cause));
-                }
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
-                }
-
-                self.jenkins_client
-                    .get_with_params(
-                        &Path::BuildJob {
-                            name: self.job_name,
-                        },
-                        &qps,
-                    )
-                    .await?
+    ///
+    /// Builds with parameters or files are sent to `buildWithParameters`, other builds to
+    /// `build`. The token, cause and delay are sent as query parameters, and can be combined
+    /// with any parameters.
+    ///
+    /// # Errors
+    /// If validation is enabled and the parameters don't match the parameters definitions
+    /// of the job, this will return an
+    /// [`Error::InvalidBuildParameters`](../../client/enum.Error.html#variant.InvalidBuildParameters)
+    /// without triggering the build
+    pub async fn send(mut self) -> Result<ShortQueueItem> {
+        if let Some(error) = self.parameters_error.take() {
+            return Err(error);
+        }
+        if self.validate {
+            let definitions = self
+                .jenkins_client
+                .get_parameter_definitions(&self.path)
+                .await?;
+            let files: Vec<&str> = self.files.iter().map(|file| file.name.as_str()).collect();
+            let issues = self
+                .parameters
+                .clone()
+                .unwrap_or_default()
+                .validate_with_files(&definitions, &files);
+            if !issues.is_empty() {
+                return Err(client::Error::InvalidBuildParameters { issues }.into());
             }
-            (Some(token), Some(parameters)) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
-                }
-                self.jenkins_client
-                    .post_with_body(
-                        &Path::BuildJobWithParameters {
-                            name: self.job_name,
-                        },
-                        format!("token={token}&{parameters}"),
-                        &qps,
-                    )
-                    .await?
+        }
+
+        let with_parameters = self.parameters.is_some() || !self.files.is_empty();
+        let job_full_name = self.path.job_full_name();
+        let bound_delay = format!("{}", self.delay.unwrap_or(0));
+        let mut qps: Vec<(&str, &str)> = Vec::new();
+        let path = if self.build_token_root {
+            qps.push(("job", job_full_name.as_deref().unwrap_or_default()));
+            if with_parameters {
+                Path::BuildByTokenWithParameters
+            } else {
+                Path::BuildByToken
             }
-            (None, None) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
-                }
+        } else {
+            self.path
+                .clone()
+                .map_innermost(|path| match path {
+                    Path::Job { name, .. } if with_parameters => {
+                        Some(Path::BuildJobWithParameters { name })
+                    }
+                    Path::Job { name, .. } => Some(Path::BuildJob { name }),
+                    _ => None,
+                })
+                .ok_or_else(|| client::Error::InvalidUrl {
+                    url: self.path.to_string(),
+                    expected: client::error::ExpectedType::Job,
+                })?
+        };
+        if let Some(token) = self.token {
+            qps.push(("token", token));
+        }
+        if let Some(cause) = self.cause {
+            qps.push(("cause", cause));
+        }
+        if self.delay.is_some() {
+            qps.push(("delay", &bound_delay));
+        }
+
+        let response = if self.files.is_empty() {
+            let body = self
+                .parameters
+                .as_ref()
+                .map(BuildParameters::to_form)
+                .transpose()?
+                .unwrap_or_default();
+            let has_password = self.parameters.iter().any(BuildParameters::has_password);
+            if has_password {
                 self.jenkins_client
-                    .post_with_body(
-                        &Path::BuildJob {
-                            name: self.job_name,
-                        },
-                        "",
-                        &qps,
-                    )
+                    .post_with_body(&path, SecretBody(body), &qps)
                     .await?
-            }
-            (None, Some(parameters)) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
-                }
+            } else {
                 self.jenkins_client
-                    .post_with_body(
-                        &Path::BuildJobWithParameters {
-                            name: self.job_name,
-                        },
-                        parameters,
-                        &qps,
-                    )
+                    .post_with_body(&path, body, &qps)
                     .await?
             }
+        } else {
+            // files can only be uploaded as `multipart/form-data`, other parameters are sent
+            // as text parts of the same form
+            let form = self
+                .parameters
+                .as_ref()
+                .map(BuildParameters::to_multipart)
+                .unwrap_or_default();
+            let form = self.files.into_iter().fold(form, |form, file| {
+                form.part(
+                    file.name,
+                    file.content.into_part().file_name(file.file_name),
+                )
+            });
+            self.jenkins_client
+                .post_multipart(&path, form, &qps)
+                .await?
         };
+        Self::queue_item_from_response(&response)
+    }
+
+    fn queue_item_from_response(response: &reqwest::Response) -> Result<ShortQueueItem> {
         if let Some(location) = response.headers().get(LOCATION) {
             Ok(ShortQueueItem {
                 url: location.to_str().unwrap().to_string(),
@@ -174,21 +518,30 @@ impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
     }
 
     /// Trigger the build remotely with a token and a cause
-    pub fn remotely_with_token_and_cause(
+    pub fn remotely_with_token_and_cause(mut self, token: &'d str, cause: Option<&'c str>) -> Self {
+        self.token = Some(token);
+        self.cause = cause;
+        self
+    }
+
+    /// Trigger the build remotely with a token and a cause, through the `buildByToken`
+    /// endpoints of the
+    /// [Build Authorization Token Root](https://plugins.jenkins.io/build-token-root/) plugin.
+    /// Those endpoints don't require to be authenticated to Jenkins, nor a CSRF crumb
+    pub fn remotely_with_build_token_root(
         mut self,
         token: &'d str,
         cause: Option<&'c str>,
-    ) -> Result<Self> {
-        self.token = Some(token);
-        self.cause = cause;
-        Ok(self)
+    ) -> Self {
+        self.build_token_root = true;
+        self.remotely_with_token_and_cause(token, cause)
     }
 
     /// Build with parameters
     ///
     /// Supported parameters type: Boolean, Choice, Multi-line string, Password, Run, String
     ///
-    /// Unsupported parameters type: File, Credentials
+    /// Unsupported parameters type: File (see [`with_file_parameter`](#method.with_file_parameter)), Credentials
     /// # Errors
     /// If used on a `Job` without parameters, sending this build will return an
     /// [`Error::IllegalState`](../enum.Error.html#variant.IllegalState)
@@ -196,12 +549,304 @@ impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
     /// If used with invalid parameters type / value, sending this build will return an
     /// [`Error::IllegalArgument`](../enum.Error.html#variant.IllegalArgument)
     ///
-    /// This methods will return an error if serializing `parameters` fails.
-    pub fn with_parameters<T: Serialize>(mut self, parameters: &T) -> Result<Self> {
-        if self.token.is_some() {
-            return Err(client::Error::UnsupportedBuildConfiguration.into());
+    /// If serializing `parameters` fails, sending this build will return the serialization
+    /// error
+    pub fn with_parameters<T: Serialize>(mut self, parameters: &T) -> Self {
+        match BuildParameters::from_serializable(parameters) {
+            Ok(parameters) => self.with_build_parameters(parameters),
+            Err(error) => {
+                self.parameters_error = Some(error);
+                self
+            }
         }
-        self.parameters = Some(serde_urlencoded::to_string(parameters)?);
-        Ok(self)
+    }
+
+    /// Build with typed parameters
+    ///
+    /// # Errors
+    /// If used on a `Job` without parameters, sending this build will return an
+    /// [`Error::IllegalState`](../../client/enum.Error.html#variant.IllegalState)
+    ///
+    /// If used with invalid parameters type / value, sending this build will return an
+    /// [`Error::IllegalArgument`](../../client/enum.Error.html#variant.IllegalArgument)
+    pub fn with_build_parameters(mut self, parameters: BuildParameters) -> Self {
+        self.parameters = Some(parameters);
+        self.parameters_error = None;
+        self
+    }
+
+    /// Upload a file for a file parameter. The build will be sent as `multipart/form-data`, and
+    /// can be combined with other parameters and with a remote trigger token
+    ///
+    /// ```rust,no_run
+    /// # async fn example(jenkins: &jenkins_api::Jenkins) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let queue_item = jenkins
+    ///     .job_builder("parameterized job")?
+    ///     .with_file_parameter("archive", "archive.zip", std::fs::read("archive.zip")?)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_file_parameter<C: Into<FileContent>>(
+        mut self,
+        name: &str,
+        file_name: &str,
+        content: C,
+    ) -> Self {
+        self.files.retain(|file| file.name != name);
+        self.files.push(FileParameter {
+            name: name.to_string(),
+            file_name: file_name.to_string(),
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Validate the parameters against the parameters definitions of the job before
+    /// triggering the build
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions() -> Vec<CommonParameterDefinition> {
+        serde_json::from_str(
+            r#"[
+                {
+                    "_class": "hudson.model.BooleanParameterDefinition",
+                    "defaultParameterValue": {"name": "bool-param", "value": false},
+                    "name": "bool-param",
+                    "type": "BooleanParameterDefinition"
+                },
+                {
+                    "_class": "hudson.model.ChoiceParameterDefinition",
+                    "defaultParameterValue": {"name": "choose between", "value": "value1"},
+                    "name": "choose between",
+                    "type": "ChoiceParameterDefinition",
+                    "choices": ["value1", "value2"]
+                },
+                {
+                    "_class": "hudson.model.FileParameterDefinition",
+                    "defaultParameterValue": null,
+                    "name": "archive",
+                    "type": "FileParameterDefinition"
+                }
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn can_validate_parameters() {
+        let issues = BuildParameters::new()
+            .with_bool("bool-param", true)
+            .with_choice("choose between", "value2")
+            .validate_with_files(&definitions(), &["archive"]);
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn string_is_not_a_file() {
+        let issues = BuildParameters::new()
+            .with_string("archive", "file.zip")
+            .validate(&definitions());
+
+        assert_eq!(
+            issues,
+            vec![ParameterIssue::InvalidType {
+                name: "archive".to_string(),
+                expected: "FileParameterDefinition".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn can_repeat_parameters() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://localhost:8080")
+            .build()
+            .unwrap();
+
+        let builder = jenkins_client
+            .job_builder("myjob")
+            .unwrap()
+            .with_parameters(&[("choices", "a"), ("choices", "b")]);
+
+        assert_eq!(
+            builder.parameters.unwrap().to_form().unwrap(),
+            "choices=a&choices=b"
+        );
+    }
+
+    #[test]
+    fn can_find_invalid_parameters() {
+        let issues = BuildParameters::new()
+            .with_string("bool param", "true")
+            .with_choice("choose between", "value3")
+            .validate(&definitions());
+
+        assert_eq!(
+            issues,
+            vec![
+                ParameterIssue::UnknownParameter {
+                    name: "bool param".to_string()
+                },
+                ParameterIssue::InvalidChoice {
+                    name: "choose between".to_string(),
+                    value: "value3".to_string(),
+                    choices: vec!["value1".to_string(), "value2".to_string()],
+                },
+                ParameterIssue::MissingValue {
+                    name: "archive".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_validate_file_parameters() {
+        let issues = BuildParameters::new().validate_with_files(&definitions(), &["archive"]);
+        assert!(issues.is_empty());
+
+        let issues = BuildParameters::new()
+            .validate_with_files(&definitions(), &["archive", "bool-param", "other"]);
+        assert_eq!(
+            issues,
+            vec![
+                ParameterIssue::InvalidType {
+                    name: "bool-param".to_string(),
+                    expected: "BooleanParameterDefinition".to_string(),
+                },
+                ParameterIssue::UnknownParameter {
+                    name: "other".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn can_build_with_file_parameter() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("POST", "/job/myjob/buildWithParameters")
+            .match_query(mockito::Matcher::Exact("token=mytoken".into()))
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data; boundary=".into()),
+            )
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#"name="bool-param"\r\n\r\ntrue\r\n"#.into()),
+                mockito::Matcher::Regex(r#"name="secret"\r\n\r\nhunter2\r\n"#.into()),
+                mockito::Matcher::Regex(r#"name="archive"; filename="archive.txt""#.into()),
+                mockito::Matcher::Regex("file content".into()),
+            ]))
+            .with_status(201)
+            .with_header("location", &format!("{}/queue/item/1/", s.url()))
+            .create();
+
+        let queue_item = jenkins_client
+            .job_builder("myjob")
+            .unwrap()
+            .with_build_parameters(
+                BuildParameters::new()
+                    .with_bool("bool-param", true)
+                    .with_password("secret", "hunter2"),
+            )
+            .remotely_with_token_and_cause("mytoken", None)
+            .with_file_parameter("archive", "archive.txt", "file content".to_string())
+            .send()
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(queue_item.url, format!("{}/queue/item/1/", s.url()));
+    }
+
+    #[tokio::test]
+    async fn can_build_remotely_with_parameters_and_cause() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("POST", "/job/myjob/buildWithParameters")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("token".into(), "mytoken".into()),
+                mockito::Matcher::UrlEncoded("cause".into(), "my cause".into()),
+                mockito::Matcher::UrlEncoded("delay".into(), "10".into()),
+            ]))
+            .match_body("free+string+param=my+value")
+            .with_status(201)
+            .with_header("location", &format!("{}/queue/item/1/", s.url()))
+            .create();
+
+        let _ = jenkins_client
+            .job_builder("myjob")
+            .unwrap()
+            .remotely_with_token_and_cause("mytoken", Some("my cause"))
+            .with_delay(10)
+            .with_build_parameters(
+                BuildParameters::new().with_string("free string param", "my value"),
+            )
+            .send()
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn can_build_with_build_token_root() {
+        let mut s = mockito::Server::new_async().await;
+
+        // CSRF protection is enabled, but no crumb is requested for a token authenticated build
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let crumb = s
+            .mock("GET", "/crumbIssuer/api/json")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create();
+        let mock = s
+            .mock("POST", "/buildByToken/build")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("job".into(), "my folder/myjob".into()),
+                mockito::Matcher::UrlEncoded("token".into(), "mytoken".into()),
+            ]))
+            .with_status(201)
+            .with_header("location", &format!("{}/queue/item/1/", s.url()))
+            .create();
+
+        let url = format!("{}/job/my%20folder/job/myjob/", s.url());
+        let _ = JobBuilder::with_path(jenkins_client.url_to_path(&url), &jenkins_client)
+            .remotely_with_build_token_root("mytoken", None)
+            .send()
+            .await
+            .unwrap();
+
+        mock.assert();
+        crumb.assert();
+    }
+
+    #[test]
+    fn can_hide_password_values() {
+        let parameters = BuildParameters::new().with_password("secret", "hunter2");
+
+        assert!(!format!("{:?}", parameters).contains("hunter2"));
     }
 }
@@ -10,6 +10,8 @@ use crate::action::CommonAction;
 use crate::build::{CommonBuild, ShortBuild};
 use crate::client::{self, Result};
 use crate::client_internals::{Name, Path};
+use crate::property::parameters::CommonParameterDefinition;
+use crate::property::{CommonProperty, ParametersDefinitionProperty};
 use crate::queue::ShortQueueItem;
 use crate::view::ViewName;
 
@@ -83,7 +85,7 @@ pub struct ShortJob<T: Job = CommonJob> {
     pub extra_fields: Option<serde_json::Value>,
 
     #[serde(skip)]
-    job_type: PhantomData<T>,
+    pub(crate) job_type: PhantomData<T>,
 }
 impl<T> ShortJob<T>
 where
@@ -500,6 +502,38 @@ specialize!(CommonJob => Job);
 
 impl CommonJob {}
 
+#[derive(Deserialize)]
+struct JobProperties {
+    #[serde(default)]
+    property: Vec<CommonProperty>,
+}
+
+impl Jenkins {
+    /// Get the parameters definitions of the `Job` at `path`
+    pub(crate) async fn get_parameter_definitions(
+        &self,
+        path: &Path<'_>,
+    ) -> Result<Vec<CommonParameterDefinition>> {
+        let job: JobProperties = self
+            .get_with_params(
+                path,
+                [(
+                    "tree",
+                    "property[parameterDefinitions[name,description,type,trim,choices,projectName,filter,value,defaultValue,multiSelectDelimiter,branchFilter,tagFilter,defaultParameterValue[name,value,jobName,number]]]",
+                )],
+            )
+            .await?
+            .json()
+            .await?;
+        Ok(job
+            .property
+            .iter()
+            .filter_map(|property| property.as_variant::<ParametersDefinitionProperty>().ok())
+            .flat_map(|property| property.parameter_definitions)
+            .collect())
+    }
+}
+
 /// Common trait for jobs that can be build
 pub trait BuildableJob: Job + Sized {
     /// Build this job
@@ -517,6 +551,29 @@ pub trait BuildableJob: Job + Sized {
     ) -> Result<JobBuilder<'a, 'b, 'c, 'd>> {
         JobBuilder::new(self, jenkins_client)
     }
+
+    /// Get the definitions of the parameters of this job, with their default values
+    fn parameter_definitions(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> impl Future<Output = Result<Vec<CommonParameterDefinition>>> + Send {
+        async {
+            let path = jenkins_client
+                .url_to_path(self.url())
+                .map_innermost(|path| match path {
+                    Path::Job {
+                        configuration: None,
+                        ..
+                    } => Some(path),
+                    _ => None,
+                })
+                .ok_or_else(|| client::Error::InvalidUrl {
+                    url: self.url().to_string(),
+                    expected: client::error::ExpectedType::Job,
+                })?;
+            jenkins_client.get_parameter_definitions(&path).await
+        }
+    }
 }
 
 /// Common trait for jobs that can poll a SCM
@@ -542,3 +599,57 @@ pub trait SCMPollable: Job + Sized {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::parameters::RunParameterDefinition;
+
+    #[tokio::test]
+    async fn can_get_run_parameter_with_default() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/job/myjob/api/json")
+            .match_query(mockito::Matcher::Regex("jobName%2Cnumber%5D".to_string()))
+            .with_body(
+                r#"{"property": [{
+                    "_class": "hudson.model.ParametersDefinitionProperty",
+                    "parameterDefinitions": [{
+                        "_class": "hudson.model.RunParameterDefinition",
+                        "defaultParameterValue": {
+                            "_class": "hudson.model.RunParameterValue",
+                            "name": "upstream",
+                            "jobName": "upstream-job",
+                            "number": "12"
+                        },
+                        "name": "upstream",
+                        "type": "RunParameterDefinition",
+                        "projectName": "upstream-job",
+                        "filter": "SUCCESSFUL"
+                    }]
+                }]}"#,
+            )
+            .create();
+
+        let definitions = jenkins_client
+            .get_parameter_definitions(&Path::Job {
+                name: Name::Name("myjob"),
+                configuration: None,
+            })
+            .await
+            .unwrap();
+
+        let run = definitions[0]
+            .as_variant::<RunParameterDefinition>()
+            .unwrap();
+        let default = run.default_parameter_value.unwrap();
+        assert_eq!(default.job_name, "upstream-job");
+        assert_eq!(default.number, "12");
+    }
+}
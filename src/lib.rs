@@ -50,13 +50,21 @@ pub mod client;
 pub mod helpers;
 
 pub mod action;
+pub mod blueocean;
 pub mod build;
+pub mod casc;
 pub mod changeset;
+pub mod credentials;
+pub mod fingerprint;
 pub mod home;
 pub mod job;
 pub mod nodes;
+pub mod pipeline_model;
+pub mod plugin;
 pub mod property;
 pub mod queue;
 pub mod scm;
+pub mod secret;
 pub mod user;
+pub mod version;
 pub mod view;
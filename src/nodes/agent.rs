@@ -0,0 +1,464 @@
+//! Types to create and launch a permanent agent
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::Jenkins;
+use crate::client::{self, Result};
+use crate::client_internals::{Name, Path, check_permission};
+use crate::helpers::unescape_xml;
+use crate::secret::Secret;
+
+/// Class of permanent agents
+const DUMB_SLAVE_CLASS: &str = "hudson.slaves.DumbSlave";
+
+/// How Jenkins schedules builds on an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeMode {
+    /// Use this agent as much as possible
+    #[default]
+    Normal,
+    /// Only build jobs with label expressions matching this agent
+    Exclusive,
+}
+
+/// How Jenkins starts an agent
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentLauncher {
+    /// The agent connects to Jenkins (JNLP / inbound agent)
+    Inbound {
+        /// Working directory of the remoting, relative to the remote root directory if not
+        /// absolute. Use the remote root directory if not set
+        work_dir: Option<String>,
+        /// Connect through a WebSocket instead of the TCP port for inbound agents
+        web_socket: bool,
+    },
+    /// Jenkins connects to the agent through SSH. Requires the `ssh-slaves` plugin
+    Ssh {
+        /// Host of the agent
+        host: String,
+        /// SSH port of the agent
+        port: u16,
+        /// ID of the credentials used to connect
+        credentials_id: String,
+    },
+    /// Jenkins starts the agent by running a command on the master
+    Command {
+        /// Command to run
+        command: String,
+    },
+}
+impl Default for AgentLauncher {
+    fn default() -> Self {
+        AgentLauncher::Inbound {
+            work_dir: None,
+            web_socket: false,
+        }
+    }
+}
+impl AgentLauncher {
+    fn to_json(&self) -> Value {
+        match *self {
+            AgentLauncher::Inbound {
+                ref work_dir,
+                web_socket,
+            } => json!({
+                "stapler-class": "hudson.slaves.JNLPLauncher",
+                "$class": "hudson.slaves.JNLPLauncher",
+                "workDirSettings": {
+                    "disabled": false,
+                    "workDirPath": work_dir.as_deref().unwrap_or_default(),
+                    "internalDir": "remoting",
+                    "failIfWorkDirIsMissing": false,
+                },
+                "webSocket": web_socket,
+            }),
+            AgentLauncher::Ssh {
+                ref host,
+                port,
+                ref credentials_id,
+            } => json!({
+                "stapler-class": "hudson.plugins.sshslaves.SSHLauncher",
+                "$class": "hudson.plugins.sshslaves.SSHLauncher",
+                "host": host,
+                "port": port.to_string(),
+                "credentialsId": credentials_id,
+                "sshHostKeyVerificationStrategy": {
+                    "stapler-class":
+                        "hudson.plugins.sshslaves.verifiers.KnownHostsFileKeyVerificationStrategy",
+                    "$class":
+                        "hudson.plugins.sshslaves.verifiers.KnownHostsFileKeyVerificationStrategy",
+                },
+            }),
+            AgentLauncher::Command { ref command } => json!({
+                "stapler-class": "hudson.slaves.CommandLauncher",
+                "$class": "hudson.slaves.CommandLauncher",
+                "command": command,
+            }),
+        }
+    }
+}
+
+/// When Jenkins keeps an agent online
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetentionStrategy {
+    /// Keep the agent online as much as possible
+    #[default]
+    Always,
+    /// Start the agent when there are builds waiting, and stop it when it is idle
+    Demand {
+        /// Minutes builds should wait before starting the agent
+        in_demand_delay: u32,
+        /// Minutes the agent can stay idle before being stopped
+        idle_delay: u32,
+    },
+}
+impl RetentionStrategy {
+    fn to_json(self) -> Value {
+        match self {
+            RetentionStrategy::Always => json!({
+                "stapler-class": "hudson.slaves.RetentionStrategy$Always",
+                "$class": "hudson.slaves.RetentionStrategy$Always",
+            }),
+            RetentionStrategy::Demand {
+                in_demand_delay,
+                idle_delay,
+            } => json!({
+                "stapler-class": "hudson.slaves.RetentionStrategy$Demand",
+                "$class": "hudson.slaves.RetentionStrategy$Demand",
+                "inDemandDelay": in_demand_delay.to_string(),
+                "idleDelay": idle_delay.to_string(),
+            }),
+        }
+    }
+}
+
+/// Specification of a permanent agent
+///
+/// ```rust
+/// let spec = jenkins_api::nodes::agent::AgentSpec::new("my-agent", "/home/jenkins")
+///     .with_labels(&["linux", "docker"])
+///     .with_executors(2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentSpec {
+    name: String,
+    description: String,
+    num_executors: u32,
+    remote_fs: String,
+    labels: Vec<String>,
+    mode: NodeMode,
+    launcher: AgentLauncher,
+    retention_strategy: RetentionStrategy,
+}
+impl AgentSpec {
+    /// Create the specification of an agent with one executor, launched as an inbound agent
+    pub fn new(name: &str, remote_fs: &str) -> Self {
+        AgentSpec {
+            name: name.to_string(),
+            description: String::new(),
+            num_executors: 1,
+            remote_fs: remote_fs.to_string(),
+            labels: vec![],
+            mode: NodeMode::default(),
+            launcher: AgentLauncher::default(),
+            retention_strategy: RetentionStrategy::default(),
+        }
+    }
+
+    /// Set the description of the agent
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the number of executors of the agent
+    pub fn with_executors(mut self, num_executors: u32) -> Self {
+        self.num_executors = num_executors;
+        self
+    }
+
+    /// Set the labels of the agent
+    pub fn with_labels(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|label| label.to_string()).collect();
+        self
+    }
+
+    /// Set how Jenkins schedules builds on the agent
+    pub fn with_mode(mut self, mode: NodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set how Jenkins starts the agent
+    pub fn with_launcher(mut self, launcher: AgentLauncher) -> Self {
+        self.launcher = launcher;
+        self
+    }
+
+    /// Set when Jenkins keeps the agent online
+    pub fn with_retention_strategy(mut self, retention_strategy: RetentionStrategy) -> Self {
+        self.retention_strategy = retention_strategy;
+        self
+    }
+
+    /// The form submitted by the "New Node" page
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "nodeDescription": self.description,
+            "numExecutors": self.num_executors.to_string(),
+            "remoteFS": self.remote_fs,
+            "labelString": self.labels.join(" "),
+            "mode": match self.mode {
+                NodeMode::Normal => "NORMAL",
+                NodeMode::Exclusive => "EXCLUSIVE",
+            },
+            "launcher": self.launcher.to_json(),
+            "retentionStrategy": self.retention_strategy.to_json(),
+            "nodeProperties": {"stapler-class-bag": "true"},
+            "type": DUMB_SLAVE_CLASS,
+        })
+    }
+}
+
+impl Jenkins {
+    /// Create a permanent agent
+    pub async fn create_agent(&self, spec: &AgentSpec) -> Result<()> {
+        let body = serde_urlencoded::to_string([
+            ("name", spec.name.as_str()),
+            ("type", DUMB_SLAVE_CLASS),
+            ("json", &spec.to_json().to_string()),
+        ])?;
+        let _ = check_permission(
+            self.post_with_body(&Path::ComputerCreateItem, body, &[])
+                .await,
+            "Computer/Create",
+        )?;
+        Ok(())
+    }
+
+    /// Create a permanent agent from its `config.xml`
+    ///
+    /// Jenkins doesn't accept a `config.xml` to create an agent, so the agent is first created
+    /// with default settings, then updated with `config_xml`. If the update fails, the agent is
+    /// deleted
+    pub async fn create_agent_from_config_xml(&self, name: &str, config_xml: &str) -> Result<()> {
+        self.create_agent(&AgentSpec::new(name, "/")).await?;
+        let path = Path::ComputerConfigXML {
+            name: Name::Name(name),
+        };
+        if let Err(err) = check_permission(
+            self.post_xml(&path, config_xml.to_string(), &[]).await,
+            "Computer/Configure",
+        ) {
+            let _ = self
+                .post(&Path::ComputerDelete {
+                    name: Name::Name(name),
+                })
+                .await;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// An argument of the application in a JNLP file
+static JNLP_ARGUMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<argument>(.*?)</argument>").unwrap());
+
+/// Information to launch an inbound agent, as provided by Jenkins in the JNLP file of the agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct JnlpLaunchInfo {
+    /// Secret of the agent
+    pub secret: Secret,
+    /// Name of the agent
+    pub agent_name: String,
+    /// Working directory of the remoting
+    pub work_dir: Option<String>,
+    /// Directory for the remoting internal data, relative to the working directory
+    pub internal_dir: Option<String>,
+    /// URL of Jenkins
+    pub url: Option<String>,
+    /// Tunnel to connect to Jenkins
+    pub tunnel: Option<String>,
+    /// Connect through a WebSocket
+    pub web_socket: bool,
+}
+impl JnlpLaunchInfo {
+    pub(crate) fn from_jnlp(jnlp: &str) -> Result<Self> {
+        let mut arguments = JNLP_ARGUMENT
+            .captures_iter(jnlp)
+            .map(|captures| unescape_xml(&captures[1]));
+        let (Some(secret), Some(agent_name)) = (arguments.next(), arguments.next()) else {
+            return Err(client::Error::InvalidJnlp.into());
+        };
+        let mut info = JnlpLaunchInfo {
+            secret: Secret::new(secret),
+            agent_name,
+            work_dir: None,
+            internal_dir: None,
+            url: None,
+            tunnel: None,
+            web_socket: false,
+        };
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "-workDir" => info.work_dir = arguments.next(),
+                "-internalDir" => info.internal_dir = arguments.next(),
+                "-url" => info.url = arguments.next(),
+                "-tunnel" => info.tunnel = arguments.next(),
+                "-webSocket" => info.web_socket = true,
+                _ => (),
+            }
+        }
+        Ok(info)
+    }
+
+    /// Render the command line to launch the agent with `agent.jar`, that can be downloaded
+    /// from `<jenkins url>/jnlpJars/agent.jar`. It includes the secret of the agent
+    pub fn command_line(&self) -> String {
+        let mut arguments = vec!["java", "-jar", "agent.jar"];
+        if let Some(ref url) = self.url {
+            arguments.extend(["-url", url]);
+        }
+        arguments.extend(["-secret", self.secret.expose(), "-name", &self.agent_name]);
+        if self.web_socket {
+            arguments.push("-webSocket");
+        }
+        if let Some(ref tunnel) = self.tunnel {
+            arguments.extend(["-tunnel", tunnel]);
+        }
+        if let Some(ref work_dir) = self.work_dir {
+            arguments.extend(["-workDir", work_dir]);
+        }
+        if let Some(ref internal_dir) = self.internal_dir {
+            arguments.extend(["-internalDir", internal_dir]);
+        }
+        arguments
+            .into_iter()
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Quote an argument for a POSIX shell, if needed
+fn shell_quote(argument: &str) -> String {
+    if !argument.is_empty()
+        && argument
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c))
+    {
+        argument.to_string()
+    } else {
+        format!("'{}'", argument.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_read_jnlp() {
+        let info = JnlpLaunchInfo::from_jnlp(
+            r#"<jnlp codebase="http://localhost:8080/computer/my%20agent/" spec="1.0+">
+  <information>
+    <title>Agent for my agent</title>
+    <vendor>Jenkins project</vendor>
+    <homepage href="https://jenkins-ci.org/"/>
+  </information>
+  <security>
+    <all-permissions/>
+  </security>
+  <resources>
+    <j2se version="1.8+"/>
+    <jar href="http://localhost:8080/jnlpJars/remoting.jar"/>
+  </resources>
+  <application-desc main-class="hudson.remoting.jnlp.Main">
+    <argument>0123456789abcdef</argument>
+    <argument>my agent</argument>
+    <argument>-workDir</argument>
+    <argument>/home/jenkins</argument>
+    <argument>-internalDir</argument>
+    <argument>remoting</argument>
+    <argument>-webSocket</argument>
+    <argument>-url</argument>
+    <argument>http://localhost:8080/?a=1&amp;b=2</argument>
+  </application-desc>
+</jnlp>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            info,
+            JnlpLaunchInfo {
+                secret: Secret::new("0123456789abcdef"),
+                agent_name: "my agent".to_string(),
+                work_dir: Some("/home/jenkins".to_string()),
+                internal_dir: Some("remoting".to_string()),
+                url: Some("http://localhost:8080/?a=1&b=2".to_string()),
+                tunnel: None,
+                web_socket: true,
+            }
+        );
+        assert_eq!(
+            info.command_line(),
+            "java -jar agent.jar -url 'http://localhost:8080/?a=1&b=2' \
+             -secret 0123456789abcdef -name 'my agent' -webSocket -workDir /home/jenkins \
+             -internalDir remoting"
+        );
+        assert!(!format!("{info:?}").contains("0123456789abcdef"));
+    }
+
+    #[test]
+    fn can_serialize_agent_spec() {
+        let spec = AgentSpec::new("my-agent", "/home/jenkins")
+            .with_labels(&["linux", "docker"])
+            .with_executors(2)
+            .with_mode(NodeMode::Exclusive)
+            .with_retention_strategy(RetentionStrategy::Demand {
+                in_demand_delay: 0,
+                idle_delay: 5,
+            });
+
+        let json = spec.to_json();
+
+        assert_eq!(json["name"], "my-agent");
+        assert_eq!(json["numExecutors"], "2");
+        assert_eq!(json["labelString"], "linux docker");
+        assert_eq!(json["mode"], "EXCLUSIVE");
+        assert_eq!(json["launcher"]["$class"], "hudson.slaves.JNLPLauncher");
+        assert_eq!(json["retentionStrategy"]["idleDelay"], "5");
+        assert_eq!(json["type"], "hudson.slaves.DumbSlave");
+    }
+
+    #[tokio::test]
+    async fn can_create_agent() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("POST", "/computer/doCreateItem")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("name".into(), "my-agent".into()),
+                mockito::Matcher::UrlEncoded("type".into(), "hudson.slaves.DumbSlave".into()),
+            ]))
+            .create();
+
+        jenkins_client
+            .create_agent(&AgentSpec::new("my-agent", "/home/jenkins"))
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+}
@@ -4,8 +4,15 @@ use std::collections::HashMap;
 
 use serde::{self, Deserialize, Serialize};
 
+use crate::Jenkins;
+use crate::client;
+use crate::client_internals::{Name, Path, Result, check_permission};
 use crate::helpers::Class;
 
+use super::agent::JnlpLaunchInfo;
+use super::health::{ComputerHealth, HealthThresholds};
+use super::label::Label;
+use super::load::{LoadStatistics, Timescale};
 use super::monitor;
 
 /// Helper type to act on a `Computer`
@@ -22,8 +29,183 @@ impl<'a> From<&'a String> for ComputerName<'a> {
     }
 }
 
+/// Name of the master computer in its URL before Jenkins 2.307. Later versions still accept it
+/// as an alias of `(built-in)`, so it works with all versions of Jenkins
+pub(crate) const MASTER_COMPUTER_NAME: &str = "(master)";
+/// Name of the built-in computer in its URL, since Jenkins 2.307. Older versions don't accept it
+const BUILT_IN_COMPUTER_NAME: &str = "(built-in)";
+/// Display name of the master computer before Jenkins 2.307
+const MASTER_DISPLAY_NAME: &str = "master";
+
 /// Trait implemented by specialization of computers
-pub trait Computer {}
+pub trait Computer: Sync {
+    /// Get the name of the computer, as used in its URL. Computers not defined by this crate
+    /// don't provide it by default, and the actions on them fail with an
+    /// [`Error::InvalidObjectType`](../../client/enum.Error.html#variant.InvalidObjectType)
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Run a Groovy script with the script console of this computer, returning what it
+    /// printed
+    ///
+    /// # Errors
+    /// If the script throws an exception, this will return an
+    /// [`Error::ScriptException`](../../client/enum.Error.html#variant.ScriptException)
+    fn run_script(
+        &self,
+        jenkins_client: &Jenkins,
+        script: &str,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            jenkins_client
+                .run_script_at(
+                    &Path::ComputerScriptText {
+                        name: url_name(self)?,
+                    },
+                    script,
+                )
+                .await
+        }
+    }
+
+    /// Get the load statistics of the executors of this computer
+    fn get_load_statistics(
+        &self,
+        jenkins_client: &Jenkins,
+        timescale: Timescale,
+    ) -> impl Future<Output = Result<LoadStatistics>> + Send {
+        async move {
+            jenkins_client
+                .get_load_statistics_at(
+                    &Path::ComputerLoadStatistics {
+                        name: url_name(self)?,
+                    },
+                    timescale,
+                )
+                .await
+        }
+    }
+
+    /// Get the config.xml file for this computer
+    fn get_config_xml(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            Ok(check_permission(
+                jenkins_client
+                    .get_blob(&Path::ComputerConfigXML {
+                        name: url_name(self)?,
+                    })
+                    .await,
+                "Computer/ExtendedRead",
+            )?
+            .text()
+            .await?)
+        }
+    }
+
+    /// Replace the config.xml file for this computer
+    fn update_config_xml(
+        &self,
+        jenkins_client: &Jenkins,
+        config_xml: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let _ = check_permission(
+                jenkins_client
+                    .post_xml(
+                        &Path::ComputerConfigXML {
+                            name: url_name(self)?,
+                        },
+                        config_xml.to_string(),
+                        &[],
+                    )
+                    .await,
+                "Computer/Configure",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Delete this computer
+    fn delete(&self, jenkins_client: &Jenkins) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let _ = check_permission(
+                jenkins_client
+                    .post(&Path::ComputerDelete {
+                        name: url_name(self)?,
+                    })
+                    .await,
+                "Computer/Delete",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Mark this computer temporarily offline with a message, or back online if it was
+    /// temporarily offline
+    fn toggle_offline(
+        &self,
+        jenkins_client: &Jenkins,
+        message: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let _ = check_permission(
+                jenkins_client
+                    .post_with_body(
+                        &Path::ComputerToggleOffline {
+                            name: url_name(self)?,
+                        },
+                        "",
+                        &[("offlineMessage", message.unwrap_or_default())],
+                    )
+                    .await,
+                "Computer/Disconnect",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Launch the agent of this computer
+    fn launch(&self, jenkins_client: &Jenkins) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let _ = check_permission(
+                jenkins_client
+                    .post(&Path::ComputerLaunchAgent {
+                        name: url_name(self)?,
+                    })
+                    .await,
+                "Computer/Connect",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Disconnect the agent of this computer, with a message
+    fn disconnect(
+        &self,
+        jenkins_client: &Jenkins,
+        message: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let _ = check_permission(
+                jenkins_client
+                    .post_with_body(
+                        &Path::ComputerDisconnect {
+                            name: url_name(self)?,
+                        },
+                        "",
+                        &[("offlineMessage", message.unwrap_or_default())],
+                    )
+                    .await,
+                "Computer/Disconnect",
+            )?;
+            Ok(())
+        }
+    }
+}
 
 macro_rules! computer_with_common_fields_and_impl {
     (
@@ -78,7 +260,7 @@ macro_rules! computer_with_common_fields_and_impl {
             /// Labels assigned to the computer
             pub assigned_labels: Vec<AssignedLabel>,
 
-            // TODO: actions, assignedLabels, loadStatistics
+            // TODO: actions
 
             $(
                 $(#[$field_attr])*
@@ -89,7 +271,29 @@ macro_rules! computer_with_common_fields_and_impl {
                 $private_field: $private_field_type,
             )*)*
         }
-        impl Computer for $name {}
+        impl $name {
+            /// Read the data of the built-in monitors of this computer
+            ///
+            /// Monitors data is only available when the computer was retrieved with enough
+            /// depth, for example with `Jenkins::get_node`, or with `Jenkins::get_nodes` and a
+            /// depth of 2
+            pub fn monitors(&self) -> monitor::Monitors {
+                monitor::Monitors::from_data(&self.monitor_data)
+            }
+
+            /// Check the health of this computer against `thresholds`, from its monitors data
+            pub fn health(&self, thresholds: &HealthThresholds) -> ComputerHealth {
+                ComputerHealth::check(
+                    self.offline.then(|| {
+                        self.offline_cause_reason
+                            .clone()
+                            .filter(|reason| !reason.is_empty())
+                    }),
+                    &self.monitors(),
+                    thresholds,
+                )
+            }
+        }
     };
 }
 
@@ -113,6 +317,44 @@ computer_with_common_fields_and_impl!(/// A Jenkins `Computer`
     }
 );
 specialize!(CommonComputer => Computer);
+impl Computer for CommonComputer {
+    fn name(&self) -> Option<&str> {
+        Some(computer_name(self.class.as_deref(), &self.display_name))
+    }
+}
+
+/// Name of a computer in its URL, from its class and its display name
+pub(crate) fn computer_name<'a>(class: Option<&str>, display_name: &'a str) -> &'a str {
+    if class == Some(MasterComputer::with_class()) {
+        master_computer_name(display_name)
+    } else {
+        display_name
+    }
+}
+
+/// Name of the master computer in its URL, as named by the version of Jenkins that provided
+/// its display name: `(master)` before Jenkins 2.307, that also renamed its display name, and
+/// `(built-in)` since then. Both are accepted by recent versions, but only the first one by
+/// older versions
+fn master_computer_name(display_name: &str) -> &'static str {
+    if display_name == MASTER_DISPLAY_NAME {
+        MASTER_COMPUTER_NAME
+    } else {
+        BUILT_IN_COMPUTER_NAME
+    }
+}
+
+/// Name of a computer in its URL, failing if its type doesn't provide it
+fn url_name<C: Computer + ?Sized>(computer: &C) -> Result<Name<'_>> {
+    computer.name().map(Name::Name).ok_or_else(|| {
+        client::Error::InvalidObjectType {
+            object_type: client::error::ExpectedType::Computer,
+            variant_name: std::any::type_name::<C>().to_string(),
+            action: client::error::Action::GetField("name"),
+        }
+        .into()
+    })
+}
 
 computer_with_common_fields_and_impl!(
     /// The master computer
@@ -121,6 +363,11 @@ computer_with_common_fields_and_impl!(
     pub struct MasterComputer {}
 );
 register_class!("hudson.model.Hudson$MasterComputer" => MasterComputer);
+impl Computer for MasterComputer {
+    fn name(&self) -> Option<&str> {
+        Some(master_computer_name(&self.display_name))
+    }
+}
 
 computer_with_common_fields_and_impl!(
     /// A slave computer
@@ -128,7 +375,29 @@ computer_with_common_fields_and_impl!(
     #[serde(rename_all = "camelCase")]
     pub struct SlaveComputer {}
 );
-register_class!("hudson.slave.SlaveComputer" => SlaveComputer);
+register_class!("hudson.slaves.SlaveComputer" => SlaveComputer);
+impl Computer for SlaveComputer {
+    fn name(&self) -> Option<&str> {
+        Some(&self.display_name)
+    }
+}
+impl SlaveComputer {
+    /// Get the secret and the arguments to launch this agent as an inbound agent, read from
+    /// its JNLP file
+    pub async fn get_agent_secret(&self, jenkins_client: &Jenkins) -> Result<JnlpLaunchInfo> {
+        let jnlp = check_permission(
+            jenkins_client
+                .get_blob(&Path::ComputerJnlp {
+                    name: url_name(self)?,
+                })
+                .await,
+            "Computer/Connect",
+        )?
+        .text()
+        .await?;
+        JnlpLaunchInfo::from_jnlp(&jnlp)
+    }
+}
 
 /// An `Executor` of a `Computer`
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -164,6 +433,15 @@ pub enum ExecutorProgress {
     /// Nothing
     None(i32),
 }
+impl ExecutorProgress {
+    /// Progress in percent, if known
+    pub fn percent(self) -> Option<u32> {
+        match self {
+            ExecutorProgress::Percent(percent) => Some(percent),
+            ExecutorProgress::None(_) => None,
+        }
+    }
+}
 
 /// A label assigned to a computer.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -171,3 +449,43 @@ pub struct AssignedLabel {
     /// Name of the label.
     pub name: String,
 }
+impl AssignedLabel {
+    /// Get the full details of the `Label`
+    pub async fn get_full_label(&self, jenkins_client: &Jenkins) -> Result<Label> {
+        jenkins_client.get_label(&self.name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_name_master_computer() {
+        let class = Some(MasterComputer::with_class());
+
+        assert_eq!(computer_name(class, "master"), "(master)");
+        assert_eq!(computer_name(class, "Built-In Node"), "(built-in)");
+        assert_eq!(computer_name(None, "agent"), "agent");
+    }
+
+    #[tokio::test]
+    async fn computer_without_name_is_rejected() {
+        struct CloudComputer;
+        impl Computer for CloudComputer {}
+
+        let jenkins_client = crate::JenkinsBuilder::new("http://localhost:1")
+            .build()
+            .unwrap();
+
+        let error = CloudComputer
+            .run_script(&jenkins_client, "println('hello')")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<client::Error>(),
+            Some(client::Error::InvalidObjectType { .. })
+        ));
+    }
+}
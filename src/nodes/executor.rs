@@ -0,0 +1,320 @@
+//! Executors of all the computers, with the builds they are running
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client_internals::{Name, Path, Result, check_permission};
+
+use super::computer::computer_name;
+
+/// Fields of the executors requested to Jenkins
+const EXECUTOR_TREE: &str = "number,idle,likelyStuck,progress,\
+                             currentExecutable[url,number,fullDisplayName,timestamp,estimatedDuration]";
+
+/// A build running on an executor. Not all executables are builds: the part of a pipeline
+/// running in a `node` block is a placeholder, without most of those fields
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorBuild {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// URL of the build
+    pub url: Option<String>,
+    /// Build number
+    pub number: Option<u32>,
+    /// Display name of the build, including the job name
+    pub full_display_name: Option<String>,
+    /// When the build started, in milliseconds since epoch
+    pub timestamp: Option<u64>,
+    /// Estimated duration of the build, in milliseconds. -1 if unknown
+    pub estimated_duration: Option<i64>,
+}
+
+/// An executor of a computer
+#[derive(Debug, Clone)]
+pub struct ExecutorInfo {
+    /// Name of the computer, as used in its URL
+    pub computer_name: String,
+    /// Executor number on the computer
+    pub number: u32,
+    /// Is this a one-off executor, used for example by the top level of a pipeline
+    pub one_off: bool,
+    /// Is the executor idle
+    pub idle: bool,
+    /// Does Jenkins consider the build likely stuck
+    pub likely_stuck: bool,
+    /// Progress of the build, in percent, if known
+    pub progress: Option<u32>,
+    /// Build currently running. Will be `None` if the executor is idle, or if it is running
+    /// a build the current user doesn't have permissions to see
+    pub current_build: Option<ExecutorBuild>,
+}
+impl ExecutorInfo {
+    /// Time elapsed since the current build started
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed_at(SystemTime::now())
+    }
+
+    fn elapsed_at(&self, now: SystemTime) -> Option<Duration> {
+        let build = self.current_build.as_ref()?;
+        let started = UNIX_EPOCH + Duration::from_millis(build.timestamp?);
+        Some(now.duration_since(started).unwrap_or_default())
+    }
+
+    /// Estimated time remaining for the current build, zero if it is already overdue
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        self.estimated_remaining_at(SystemTime::now())
+    }
+
+    fn estimated_remaining_at(&self, now: SystemTime) -> Option<Duration> {
+        let estimated = self.estimated_duration()?;
+        Some(estimated.saturating_sub(self.elapsed_at(now)?))
+    }
+
+    fn estimated_duration(&self) -> Option<Duration> {
+        let build = self.current_build.as_ref()?;
+        u64::try_from(build.estimated_duration?)
+            .ok()
+            .map(Duration::from_millis)
+    }
+
+    /// Is the current build stuck: either Jenkins considers it likely stuck, or it is running
+    /// for more than `factor` times its estimated duration
+    pub fn is_stuck(&self, factor: f64) -> bool {
+        self.is_stuck_at(factor, SystemTime::now())
+    }
+
+    fn is_stuck_at(&self, factor: f64, now: SystemTime) -> bool {
+        if self.likely_stuck {
+            return true;
+        }
+        match (self.elapsed_at(now), self.estimated_duration()) {
+            (Some(elapsed), Some(estimated)) if !estimated.is_zero() => {
+                elapsed.as_secs_f64() > estimated.as_secs_f64() * factor
+            }
+            _ => false,
+        }
+    }
+
+    /// Interrupt the build running on this executor
+    pub async fn interrupt(&self, jenkins_client: &Jenkins) -> Result<()> {
+        let _ = check_permission(
+            jenkins_client
+                .post(&Path::ComputerExecutorStop {
+                    name: Name::Name(&self.computer_name),
+                    number: self.number,
+                    one_off: self.one_off,
+                })
+                .await,
+            "Job/Cancel",
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InternalExecutor {
+    number: u32,
+    idle: bool,
+    likely_stuck: bool,
+    progress: i32,
+    current_executable: Option<ExecutorBuild>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InternalComputer {
+    #[serde(rename = "_class")]
+    class: Option<String>,
+    display_name: String,
+    #[serde(default)]
+    executors: Vec<InternalExecutor>,
+    #[serde(default)]
+    one_off_executors: Vec<InternalExecutor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InternalComputerSet {
+    computer: Vec<InternalComputer>,
+}
+
+impl Jenkins {
+    /// Get all the executors of all the computers, with the builds they are running
+    pub async fn get_executors(&self) -> Result<Vec<ExecutorInfo>> {
+        let computer_set: InternalComputerSet = self
+            .get_with_params(
+                &Path::Computers,
+                [(
+                    "tree",
+                    format!(
+                        "computer[_class,displayName,executors[{EXECUTOR_TREE}],\
+                         oneOffExecutors[{EXECUTOR_TREE}]]"
+                    ),
+                )],
+            )
+            .await?
+            .json()
+            .await?;
+
+        Ok(computer_set
+            .computer
+            .into_iter()
+            .flat_map(|computer| {
+                let name =
+                    computer_name(computer.class.as_deref(), &computer.display_name).to_string();
+                computer
+                    .executors
+                    .into_iter()
+                    .map(|executor| (executor, false))
+                    .chain(
+                        computer
+                            .one_off_executors
+                            .into_iter()
+                            .map(|executor| (executor, true)),
+                    )
+                    .map(move |(executor, one_off)| ExecutorInfo {
+                        computer_name: name.clone(),
+                        number: executor.number,
+                        one_off,
+                        idle: executor.idle,
+                        likely_stuck: executor.likely_stuck,
+                        progress: u32::try_from(executor.progress).ok(),
+                        current_build: executor.current_executable,
+                    })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_get_executors() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", mockito::Matcher::Regex("^/computer/".into()))
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"{
+                    "_class": "hudson.model.ComputerSet",
+                    "computer": [
+                        {
+                            "_class": "hudson.model.Hudson$MasterComputer",
+                            "displayName": "Built-In Node",
+                            "executors": [
+                                {
+                                    "currentExecutable": {
+                                        "_class": "hudson.model.FreeStyleBuild",
+                                        "url": "http://localhost:8080/job/long%20job/3/",
+                                        "number": 3,
+                                        "fullDisplayName": "long job #3",
+                                        "timestamp": 1700000000000,
+                                        "estimatedDuration": 60000
+                                    },
+                                    "idle": false,
+                                    "likelyStuck": false,
+                                    "number": 0,
+                                    "progress": 50
+                                },
+                                {
+                                    "currentExecutable": null,
+                                    "idle": true,
+                                    "likelyStuck": false,
+                                    "number": 1,
+                                    "progress": -1
+                                }
+                            ],
+                            "oneOffExecutors": []
+                        },
+                        {
+                            "_class": "hudson.slaves.SlaveComputer",
+                            "displayName": "agent",
+                            "executors": [
+                                {
+                                    "currentExecutable": {
+                                        "_class": "org.jenkinsci.plugins.workflow.support.steps.ExecutorStepExecution$PlaceholderTask$PlaceholderExecutable"
+                                    },
+                                    "idle": false,
+                                    "likelyStuck": false,
+                                    "number": 0,
+                                    "progress": -1
+                                }
+                            ],
+                            "oneOffExecutors": [
+                                {
+                                    "currentExecutable": null,
+                                    "idle": false,
+                                    "likelyStuck": true,
+                                    "number": 0,
+                                    "progress": -1
+                                }
+                            ]
+                        }
+                    ]
+                }"#,
+            )
+            .create();
+
+        let executors = jenkins_client.get_executors().await.unwrap();
+
+        assert_eq!(executors.len(), 4);
+        assert_eq!(executors[0].computer_name, "(built-in)");
+        assert_eq!(executors[0].progress, Some(50));
+        assert_eq!(executors[1].progress, None);
+        assert!(executors[2].current_build.as_ref().unwrap().url.is_none());
+        assert!(!executors[2].one_off);
+        assert!(executors[3].one_off);
+
+        let now = UNIX_EPOCH + Duration::from_millis(1700000000000 + 150_000);
+        assert_eq!(executors[0].elapsed_at(now), Some(Duration::from_secs(150)));
+        assert_eq!(
+            executors[0].estimated_remaining_at(now),
+            Some(Duration::ZERO)
+        );
+        assert!(executors[0].is_stuck_at(2.0, now));
+        assert!(!executors[0].is_stuck_at(3.0, now));
+        assert!(!executors[1].is_stuck_at(2.0, now));
+        assert_eq!(executors[2].elapsed_at(now), None);
+        assert!(!executors[2].is_stuck_at(2.0, now));
+        assert!(executors[3].is_stuck_at(2.0, now));
+    }
+
+    #[tokio::test]
+    async fn can_interrupt_executor() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("POST", "/computer/agent/oneOffExecutors/0/stop")
+            .create();
+
+        let executor = ExecutorInfo {
+            computer_name: "agent".to_string(),
+            number: 0,
+            one_off: true,
+            idle: false,
+            likely_stuck: true,
+            progress: None,
+            current_build: None,
+        };
+        executor.interrupt(&jenkins_client).await.unwrap();
+
+        mock.assert();
+    }
+}
@@ -0,0 +1,197 @@
+//! Health summary of a `Computer`, from its monitor data
+
+use super::monitor::Monitors;
+
+/// Thresholds to check the health of a `Computer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+    /// Minimum free disk space in the remote root directory, in bytes
+    pub min_disk_space: u64,
+    /// Minimum free disk space in the temporary directory, in bytes
+    pub min_temporary_space: u64,
+    /// Minimum available swap space, in bytes. Only checked if the computer has swap space
+    pub min_swap_space: u64,
+    /// Maximum clock difference with the master, in milliseconds
+    pub max_clock_difference: u64,
+    /// Maximum average response time, in milliseconds
+    pub max_response_time: u64,
+}
+impl Default for HealthThresholds {
+    /// The default thresholds of the Jenkins monitors where they exist
+    fn default() -> Self {
+        HealthThresholds {
+            min_disk_space: 1024 * 1024 * 1024,
+            min_temporary_space: 1024 * 1024 * 1024,
+            min_swap_space: 256 * 1024 * 1024,
+            max_clock_difference: 5_000,
+            max_response_time: 5_000,
+        }
+    }
+}
+
+/// An issue found when checking the health of a `Computer`
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthIssue {
+    /// The computer is offline
+    Offline {
+        /// Why is the computer offline
+        reason: Option<String>,
+    },
+    /// Not enough free disk space in the remote root directory
+    LowDiskSpace {
+        /// Path monitored
+        path: String,
+        /// Free disk space, in bytes
+        available: u64,
+    },
+    /// Not enough free disk space in the temporary directory
+    LowTemporarySpace {
+        /// Path monitored
+        path: String,
+        /// Free disk space, in bytes
+        available: u64,
+    },
+    /// Not enough available swap space
+    LowSwapSpace {
+        /// Available swap space, in bytes
+        available: u64,
+    },
+    /// The clock of the computer is too different from the clock of the master
+    ClockSkew {
+        /// Clock difference, in milliseconds
+        difference: i64,
+    },
+    /// The computer responds too slowly
+    SlowResponse {
+        /// Average response time, in milliseconds
+        average: u64,
+    },
+}
+
+/// Health summary of a `Computer`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComputerHealth {
+    /// Issues found
+    pub issues: Vec<HealthIssue>,
+}
+impl ComputerHealth {
+    /// Check the health of a computer from its state and its monitors
+    pub(crate) fn check(
+        offline: Option<Option<String>>,
+        monitors: &Monitors,
+        thresholds: &HealthThresholds,
+    ) -> Self {
+        let mut issues = vec![];
+        if let Some(reason) = offline {
+            issues.push(HealthIssue::Offline { reason });
+        }
+        if let Some(ref disk_space) = monitors.disk_space
+            && disk_space.size < thresholds.min_disk_space
+        {
+            issues.push(HealthIssue::LowDiskSpace {
+                path: disk_space.path.clone(),
+                available: disk_space.size,
+            });
+        }
+        if let Some(ref temporary_space) = monitors.temporary_space
+            && temporary_space.size < thresholds.min_temporary_space
+        {
+            issues.push(HealthIssue::LowTemporarySpace {
+                path: temporary_space.path.clone(),
+                available: temporary_space.size,
+            });
+        }
+        if let Some(swap_space) = monitors.swap_space
+            && swap_space.total_swap_space > 0
+            && swap_space.available_swap_space < thresholds.min_swap_space
+        {
+            issues.push(HealthIssue::LowSwapSpace {
+                available: swap_space.available_swap_space,
+            });
+        }
+        if let Some(clock) = monitors.clock
+            && clock.diff.unsigned_abs() > thresholds.max_clock_difference
+        {
+            issues.push(HealthIssue::ClockSkew {
+                difference: clock.diff,
+            });
+        }
+        if let Some(response_time) = monitors.response_time
+            && response_time.average > thresholds.max_response_time
+        {
+            issues.push(HealthIssue::SlowResponse {
+                average: response_time.average,
+            });
+        }
+        ComputerHealth { issues }
+    }
+
+    /// Is the computer healthy
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::nodes::monitor::Data;
+
+    #[test]
+    fn can_check_health() {
+        let data: HashMap<String, Data> = serde_json::from_str(
+            r#"{
+                "hudson.node_monitors.SwapSpaceMonitor": {
+                    "_class": "hudson.node_monitors.SwapSpaceMonitor$MemoryUsage2",
+                    "availablePhysicalMemory": 1000000000,
+                    "availableSwapSpace": 1000,
+                    "totalPhysicalMemory": 8000000000,
+                    "totalSwapSpace": 2000000000
+                },
+                "hudson.node_monitors.TemporarySpaceMonitor": {
+                    "_class": "hudson.node_monitors.DiskSpaceMonitorDescriptor$DiskSpace",
+                    "timestamp": 1700000000000,
+                    "path": "/tmp",
+                    "size": 2000000000
+                },
+                "hudson.node_monitors.DiskSpaceMonitor": {
+                    "_class": "hudson.node_monitors.DiskSpaceMonitorDescriptor$DiskSpace",
+                    "timestamp": 1700000000000,
+                    "path": "/var/jenkins",
+                    "size": 1000
+                },
+                "hudson.node_monitors.ArchitectureMonitor": "Linux (amd64)",
+                "hudson.node_monitors.ResponseTimeMonitor": {
+                    "_class": "hudson.node_monitors.ResponseTimeMonitor$Data",
+                    "timestamp": 1700000000000,
+                    "average": 10
+                },
+                "hudson.node_monitors.ClockMonitor": {
+                    "_class": "hudson.util.ClockDifference",
+                    "diff": -10000
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let monitors = Monitors::from_data(&data);
+        assert_eq!(monitors.architecture.as_deref(), Some("Linux (amd64)"));
+        assert_eq!(monitors.temporary_space.as_ref().unwrap().path, "/tmp");
+
+        let health = ComputerHealth::check(None, &monitors, &HealthThresholds::default());
+        assert_eq!(
+            health.issues,
+            vec![
+                HealthIssue::LowDiskSpace {
+                    path: "/var/jenkins".to_string(),
+                    available: 1000,
+                },
+                HealthIssue::LowSwapSpace { available: 1000 },
+                HealthIssue::ClockSkew { difference: -10000 },
+            ]
+        );
+        assert!(!health.is_healthy());
+    }
+}
@@ -0,0 +1,113 @@
+//! Labels of computers, and the executors they group
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client_internals::{Name, Path, Result};
+use crate::job::ShortJob;
+
+use super::load::{LoadStatistics, Timescale};
+
+/// Helper type to act on a `Label`
+#[derive(Debug)]
+pub struct LabelName<'a>(pub &'a str);
+impl<'a> From<&'a str> for LabelName<'a> {
+    fn from(v: &'a str) -> LabelName<'a> {
+        LabelName(v)
+    }
+}
+impl<'a> From<&'a String> for LabelName<'a> {
+    fn from(v: &'a String) -> LabelName<'a> {
+        LabelName(v)
+    }
+}
+
+/// A node having a label
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelNode {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the node, empty for the master
+    pub node_name: String,
+}
+
+/// A cloud that can provision nodes for a label
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelCloud {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the cloud
+    pub name: Option<String>,
+}
+
+/// A label, or a label expression, grouping computers
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the label, or label expression
+    pub name: String,
+    /// Description of the label
+    pub description: Option<String>,
+    /// Number of busy executors
+    pub busy_executors: u32,
+    /// Number of idle executors
+    pub idle_executors: u32,
+    /// Number of executors
+    pub total_executors: u32,
+    /// Are all the nodes with this label offline
+    pub offline: bool,
+    /// Nodes with this label
+    pub nodes: Vec<LabelNode>,
+    /// Jobs that can only run on this label
+    pub tied_jobs: Vec<ShortJob>,
+    /// Clouds that can provision nodes with this label
+    pub clouds: Vec<LabelCloud>,
+
+    #[cfg(not(feature = "extra-fields-visibility"))]
+    #[serde(flatten)]
+    pub(crate) extra_fields: Option<serde_json::Value>,
+    #[cfg(feature = "extra-fields-visibility")]
+    /// Extra fields not parsed for a common object
+    #[serde(flatten)]
+    pub extra_fields: Option<serde_json::Value>,
+}
+impl Label {
+    /// Get the load statistics of the executors with this label
+    pub async fn get_load_statistics(
+        &self,
+        jenkins_client: &Jenkins,
+        timescale: Timescale,
+    ) -> Result<LoadStatistics> {
+        jenkins_client
+            .get_load_statistics_at(
+                &Path::LabelLoadStatistics {
+                    name: Name::Name(&self.name),
+                },
+                timescale,
+            )
+            .await
+    }
+}
+
+impl Jenkins {
+    /// Get a `Label` from its name, or from a label expression
+    pub async fn get_label<'a, L>(&self, label: L) -> Result<Label>
+    where
+        L: Into<LabelName<'a>>,
+    {
+        Ok(self
+            .get(&Path::Label {
+                name: Name::Name(label.into().0),
+            })
+            .await?
+            .json()
+            .await?)
+    }
+}
@@ -0,0 +1,195 @@
+//! Load statistics of computers and labels
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client_internals::{Path, Result};
+
+/// Timescale of the load statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timescale {
+    /// One point every 10 seconds
+    TenSeconds,
+    /// One point every minute
+    Minute,
+    /// One point every hour
+    Hour,
+}
+impl Timescale {
+    fn field(self) -> &'static str {
+        match self {
+            Timescale::TenSeconds => "sec10",
+            Timescale::Minute => "min",
+            Timescale::Hour => "hour",
+        }
+    }
+}
+
+/// A time series, with exponential moving averages of a value
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct TimeSeries {
+    /// Values, from the most recent to the oldest
+    pub history: Vec<f64>,
+    /// Most recent value
+    pub latest: f64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct MultiStageTimeSeries {
+    sec10: Option<TimeSeries>,
+    min: Option<TimeSeries>,
+    hour: Option<TimeSeries>,
+}
+impl MultiStageTimeSeries {
+    fn at(self, timescale: Timescale) -> TimeSeries {
+        match timescale {
+            Timescale::TenSeconds => self.sec10,
+            Timescale::Minute => self.min,
+            Timescale::Hour => self.hour,
+        }
+        .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InternalLoadStatistics {
+    #[serde(default)]
+    available_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    busy_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    connecting_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    defined_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    idle_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    online_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    queue_length: MultiStageTimeSeries,
+    #[serde(default)]
+    total_executors: MultiStageTimeSeries,
+    #[serde(default)]
+    total_queue_length: MultiStageTimeSeries,
+}
+
+/// Load statistics, at a `Timescale`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadStatistics {
+    /// Timescale of the time series
+    pub timescale: Timescale,
+    /// Executors online and idle
+    pub available_executors: TimeSeries,
+    /// Executors busy
+    pub busy_executors: TimeSeries,
+    /// Executors connecting
+    pub connecting_executors: TimeSeries,
+    /// Executors defined, online or not
+    pub defined_executors: TimeSeries,
+    /// Executors idle, online or not
+    pub idle_executors: TimeSeries,
+    /// Executors online
+    pub online_executors: TimeSeries,
+    /// Items in the queue that could be built by these executors
+    pub queue_length: TimeSeries,
+    /// Executors total, the same as online executors
+    pub total_executors: TimeSeries,
+    /// Items in the queue, including the ones these executors can't build
+    pub total_queue_length: TimeSeries,
+}
+
+/// The fields of the statistics, restricted to `timescale`
+fn tree(timescale: Timescale) -> String {
+    let field = timescale.field();
+    [
+        "availableExecutors",
+        "busyExecutors",
+        "connectingExecutors",
+        "definedExecutors",
+        "idleExecutors",
+        "onlineExecutors",
+        "queueLength",
+        "totalExecutors",
+        "totalQueueLength",
+    ]
+    .iter()
+    .map(|statistic| format!("{statistic}[{field}[history,latest]]"))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+impl Jenkins {
+    pub(crate) async fn get_load_statistics_at(
+        &self,
+        path: &Path<'_>,
+        timescale: Timescale,
+    ) -> Result<LoadStatistics> {
+        let statistics: InternalLoadStatistics = self
+            .get_with_params(path, [("tree", tree(timescale))])
+            .await?
+            .json()
+            .await?;
+        Ok(LoadStatistics {
+            timescale,
+            available_executors: statistics.available_executors.at(timescale),
+            busy_executors: statistics.busy_executors.at(timescale),
+            connecting_executors: statistics.connecting_executors.at(timescale),
+            defined_executors: statistics.defined_executors.at(timescale),
+            idle_executors: statistics.idle_executors.at(timescale),
+            online_executors: statistics.online_executors.at(timescale),
+            queue_length: statistics.queue_length.at(timescale),
+            total_executors: statistics.total_executors.at(timescale),
+            total_queue_length: statistics.total_queue_length.at(timescale),
+        })
+    }
+
+    /// Get the load statistics of all the executors of this `Jenkins` instance
+    pub async fn get_overall_load_statistics(
+        &self,
+        timescale: Timescale,
+    ) -> Result<LoadStatistics> {
+        self.get_load_statistics_at(&Path::OverallLoad, timescale)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_get_load_statistics() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/overallLoad/api/json")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "tree".into(),
+                tree(Timescale::Hour),
+            ))
+            .with_body(
+                r#"{
+                    "_class": "hudson.model.OverallLoadStatistics",
+                    "busyExecutors": {"hour": {"history": [1.5, 0.5], "latest": 1.5}},
+                    "queueLength": {"hour": {"history": [3.0, 0.0], "latest": 3.0}}
+                }"#,
+            )
+            .create();
+
+        let statistics = jenkins_client
+            .get_overall_load_statistics(Timescale::Hour)
+            .await
+            .unwrap();
+
+        assert_eq!(statistics.timescale, Timescale::Hour);
+        assert_eq!(statistics.busy_executors.history, vec![1.5, 0.5]);
+        assert_eq!(statistics.queue_length.latest, 3.0);
+        assert_eq!(statistics.idle_executors, TimeSeries::default());
+    }
+}
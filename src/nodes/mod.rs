@@ -5,7 +5,12 @@ use serde::{Deserialize, Serialize};
 use crate::Jenkins;
 use crate::client_internals::{Name, Path, Result};
 
+pub mod agent;
 pub mod computer;
+pub mod executor;
+pub mod health;
+pub mod label;
+pub mod load;
 pub mod monitor;
 
 /// List of `Computer` associated to the `Jenkins` instance
@@ -47,7 +52,7 @@ impl Jenkins {
     pub async fn get_master_node(&self) -> Result<computer::MasterComputer> {
         Ok(self
             .get(&Path::Computer {
-                name: Name::Name("(master)"),
+                name: Name::Name(computer::MASTER_COMPUTER_NAME),
             })
             .await?
             .json()
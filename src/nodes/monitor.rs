@@ -1,4 +1,6 @@
-//! Types to parse the monitor data of a `Computer`
+//! Types to parse the monitor data and the offline cause of a `Computer`
+
+use std::collections::HashMap;
 
 use serde::{self, Deserialize, Serialize};
 
@@ -55,8 +57,9 @@ pub struct SwapSpaceMonitor {
 register_class!("hudson.node_monitors.SwapSpaceMonitor$MemoryUsage2" => SwapSpaceMonitor);
 impl MonitorData for SwapSpaceMonitor {}
 
-/// Swap Space Monitor
-#[derive(Deserialize, Debug)]
+/// Disk Space Monitor, used by monitors `hudson.node_monitors.DiskSpaceMonitor` and
+/// `hudson.node_monitors.TemporarySpaceMonitor`
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DiskSpaceMonitorDescriptor {
     /// Timestamp
@@ -90,3 +93,117 @@ pub struct ClockDifference {
 }
 register_class!("hudson.util.ClockDifference" => ClockDifference);
 impl MonitorData for ClockDifference {}
+
+macro_rules! offline_cause {
+    (
+        $(#[$attr:meta])*
+        $name:ident => $class:expr
+        $(, {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident: $field_type:ty
+            ),* $(,)*
+        })*
+    ) => {
+        $(#[$attr])*
+        #[derive(Deserialize, Debug, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct $name {
+            /// When the computer was put offline, in milliseconds since epoch
+            pub timestamp: u64,
+            /// Description of the cause
+            pub description: Option<String>,
+            $($(
+                $(#[$field_attr])*
+                pub $field: $field_type,
+            )*)*
+        }
+        register_class!($class => $name);
+        impl MonitorData for $name {}
+    };
+}
+
+offline_cause!(
+    /// The computer was put offline by a user
+    UserCause => "hudson.slaves.OfflineCause$UserCause"
+);
+offline_cause!(
+    /// The channel to the computer was closed
+    ChannelTermination => "hudson.slaves.OfflineCause$ChannelTermination"
+);
+offline_cause!(
+    /// The agent of the computer failed to launch
+    LaunchFailed => "hudson.slaves.OfflineCause$LaunchFailed"
+);
+offline_cause!(
+    /// The computer was put offline with the CLI
+    ByCLI => "hudson.slaves.OfflineCause$ByCLI", {
+        /// Message given when putting the computer offline
+        message: Option<String>,
+    }
+);
+offline_cause!(
+    /// The computer was stopped by its retention strategy because it was idle
+    IdleOfflineCause => "hudson.slaves.OfflineCause$IdleOfflineCause"
+);
+offline_cause!(
+    /// The computer was put offline with a message
+    SimpleOfflineCause => "hudson.slaves.OfflineCause$SimpleOfflineCause"
+);
+
+/// Key of the architecture monitor in the monitor data of a `Computer`
+pub const ARCHITECTURE_MONITOR: &str = "hudson.node_monitors.ArchitectureMonitor";
+/// Key of the clock monitor in the monitor data of a `Computer`
+pub const CLOCK_MONITOR: &str = "hudson.node_monitors.ClockMonitor";
+/// Key of the disk space monitor in the monitor data of a `Computer`
+pub const DISK_SPACE_MONITOR: &str = "hudson.node_monitors.DiskSpaceMonitor";
+/// Key of the response time monitor in the monitor data of a `Computer`
+pub const RESPONSE_TIME_MONITOR: &str = "hudson.node_monitors.ResponseTimeMonitor";
+/// Key of the swap space monitor in the monitor data of a `Computer`
+pub const SWAP_SPACE_MONITOR: &str = "hudson.node_monitors.SwapSpaceMonitor";
+/// Key of the temporary space monitor in the monitor data of a `Computer`
+pub const TEMPORARY_SPACE_MONITOR: &str = "hudson.node_monitors.TemporarySpaceMonitor";
+
+/// Data of the built-in monitors of a `Computer`. A monitor is `None` when it didn't provide
+/// data, or when its data was not retrieved with enough depth
+#[derive(Debug, Clone, Default)]
+pub struct Monitors {
+    /// Operating system and architecture
+    pub architecture: Option<String>,
+    /// Clock difference with the master
+    pub clock: Option<ClockDifference>,
+    /// Free disk space in the remote root directory
+    pub disk_space: Option<DiskSpaceMonitorDescriptor>,
+    /// Response time of the computer
+    pub response_time: Option<ResponseTimeMonitor>,
+    /// Memory and swap space
+    pub swap_space: Option<SwapSpaceMonitor>,
+    /// Free disk space in the temporary directory
+    pub temporary_space: Option<DiskSpaceMonitorDescriptor>,
+}
+impl Monitors {
+    /// Read the built-in monitors from the monitor data of a `Computer`
+    pub fn from_data(data: &HashMap<String, Data>) -> Self {
+        fn monitor<T>(data: &HashMap<String, Data>, key: &str) -> Option<T>
+        where
+            for<'de> T: MonitorData + Class + Deserialize<'de>,
+        {
+            match data.get(key) {
+                Some(Data::MonitorData(monitor_data)) => monitor_data.as_variant().ok(),
+                _ => None,
+            }
+        }
+
+        Monitors {
+            architecture: match data.get(ARCHITECTURE_MONITOR) {
+                Some(Data::String(architecture)) => Some(architecture.clone()),
+                _ => None,
+            },
+            clock: monitor(data, CLOCK_MONITOR),
+            disk_space: monitor(data, DISK_SPACE_MONITOR),
+            response_time: monitor(data, RESPONSE_TIME_MONITOR),
+            swap_space: monitor(data, SWAP_SPACE_MONITOR),
+            temporary_space: monitor(data, TEMPORARY_SPACE_MONITOR),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Declarative pipelines, validated and converted by the `pipeline-model-definition` plugin
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::Jenkins;
+use crate::client::{self, Result, error::PipelineError};
+use crate::client_internals::{DescriptorResponse, Path, check_permission};
+
+/// Output of a successful validation
+const VALIDATION_SUCCESS: &str = "Jenkinsfile successfully validated.";
+
+/// An error from the Groovy compiler, with its location
+static LOCATED_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:WorkflowScript: \d+: )?(.*?) @ line (\d+), column (\d+)\.?$").unwrap()
+});
+
+/// Parse an error message from Jenkins, extracting its location if present
+fn parse_error(message: &str) -> PipelineError {
+    let message = message.trim();
+    match LOCATED_ERROR.captures(message.lines().next().unwrap_or_default()) {
+        Some(captures) => PipelineError {
+            message: captures[1].to_string(),
+            line: captures[2].parse().ok(),
+            column: captures[3].parse().ok(),
+        },
+        None => PipelineError {
+            message: message.to_string(),
+            line: None,
+            column: None,
+        },
+    }
+}
+
+/// Parse the output of the validation, with one error per located line
+fn parse_validation(output: &str) -> Vec<PipelineError> {
+    let output = output.trim();
+    if output.starts_with(VALIDATION_SUCCESS) {
+        return vec![];
+    }
+    let errors: Vec<PipelineError> = output
+        .lines()
+        .filter(|line| LOCATED_ERROR.is_match(line.trim()))
+        .map(parse_error)
+        .collect();
+    if errors.is_empty() {
+        vec![parse_error(output)]
+    } else {
+        errors
+    }
+}
+
+/// Collect the error messages of a conversion, that may be nested in objects and arrays
+fn collect_messages(value: &Value, messages: &mut Vec<String>) {
+    match value {
+        Value::String(message) => messages.push(message.clone()),
+        Value::Array(values) => values.iter().for_each(|v| collect_messages(v, messages)),
+        Value::Object(object) => match object.get("error") {
+            Some(error) => collect_messages(error, messages),
+            None => object.values().for_each(|v| collect_messages(v, messages)),
+        },
+        _ => (),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Conversion {
+    result: String,
+    #[serde(default)]
+    errors: Vec<Value>,
+    json: Option<Value>,
+    jenkinsfile: Option<String>,
+}
+impl Conversion {
+    fn check(self) -> Result<Self> {
+        if self.result == "success" {
+            Ok(self)
+        } else {
+            let mut messages = vec![];
+            collect_messages(&Value::Array(self.errors), &mut messages);
+            Err(client::Error::InvalidPipeline {
+                errors: messages
+                    .iter()
+                    .map(|message| parse_error(message))
+                    .collect(),
+            }
+            .into())
+        }
+    }
+}
+
+impl Jenkins {
+    /// Validate a declarative Jenkinsfile, returning the errors found. The Jenkinsfile is valid
+    /// if there are none
+    pub async fn validate_declarative_pipeline(
+        &self,
+        jenkinsfile: &str,
+    ) -> Result<Vec<PipelineError>> {
+        let body = serde_urlencoded::to_string([("jenkinsfile", jenkinsfile)])?;
+        let output = check_permission(
+            self.post_with_body(&Path::PipelineModelValidate, body, &[])
+                .await,
+            "Overall/Read",
+        )?
+        .text()
+        .await?;
+        Ok(parse_validation(&output))
+    }
+
+    async fn convert(&self, path: &Path<'_>, field: &str, value: &str) -> Result<Conversion> {
+        let body = serde_urlencoded::to_string([(field, value)])?;
+        let response: DescriptorResponse<Conversion> =
+            check_permission(self.post_with_body(path, body, &[]).await, "Overall/Read")?
+                .json()
+                .await?;
+        response
+            .into_result()?
+            .ok_or_else(|| {
+                client::Error::ErrorResponse {
+                    message: "no conversion result".to_string(),
+                }
+                .into()
+            })
+            .and_then(Conversion::check)
+    }
+
+    /// Convert a declarative Jenkinsfile to its JSON representation
+    ///
+    /// # Errors
+    /// If the Jenkinsfile is invalid, this will return an
+    /// [`Error::InvalidPipeline`](../client/enum.Error.html#variant.InvalidPipeline)
+    pub async fn convert_to_json(&self, jenkinsfile: &str) -> Result<Value> {
+        let conversion = self
+            .convert(&Path::PipelineModelToJson, "jenkinsfile", jenkinsfile)
+            .await?;
+        Ok(conversion.json.unwrap_or_default())
+    }
+
+    /// Convert the JSON representation of a declarative pipeline to a Jenkinsfile
+    ///
+    /// # Errors
+    /// If the JSON is invalid, this will return an
+    /// [`Error::InvalidPipeline`](../client/enum.Error.html#variant.InvalidPipeline)
+    pub async fn convert_to_jenkinsfile(&self, json: &Value) -> Result<String> {
+        let conversion = self
+            .convert(&Path::PipelineModelToJenkinsfile, "json", &json.to_string())
+            .await?;
+        Ok(conversion.jenkinsfile.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_validation_errors() {
+        assert!(parse_validation("Jenkinsfile successfully validated.\n").is_empty());
+
+        let errors = parse_validation(
+            r#"Errors encountered validating Jenkinsfile:
+WorkflowScript: 3: Undefined section "foo" @ line 3, column 5.
+       foo {
+       ^
+
+WorkflowScript: 1: Missing required section "agent" @ line 1, column 1.
+   pipeline {
+   ^
+"#,
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                PipelineError {
+                    message: r#"Undefined section "foo""#.to_string(),
+                    line: Some(3),
+                    column: Some(5),
+                },
+                PipelineError {
+                    message: r#"Missing required section "agent""#.to_string(),
+                    line: Some(1),
+                    column: Some(1),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn conversion_failure_is_an_error() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("POST", "/pipeline-model-converter/toJson")
+            .match_body(mockito::Matcher::Regex("^jenkinsfile=".to_string()))
+            .with_body(
+                r#"{"status": "ok", "data": {"result": "failure", "errors": [
+                    {"error": "Undefined section \"foo\" @ line 3, column 5."}
+                ]}}"#,
+            )
+            .create();
+
+        let error = jenkins_client
+            .convert_to_json("pipeline { foo { } }")
+            .await
+            .unwrap_err();
+
+        match error.downcast_ref::<client::Error>() {
+            Some(client::Error::InvalidPipeline { errors }) => {
+                assert_eq!(errors[0].line, Some(3));
+                assert_eq!(errors[0].column, Some(5));
+            }
+            _ => panic!("unexpected error {error}"),
+        }
+    }
+}
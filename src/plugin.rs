@@ -0,0 +1,236 @@
+//! Jenkins Plugins, from the plugin manager and the update center
+
+use serde::{Deserialize, Serialize};
+
+use crate::Jenkins;
+use crate::client::Result;
+use crate::client_internals::{Path, check_permission};
+use crate::helpers::escape_xml;
+
+/// Status of an installation job that succeeded, but needs a restart to be applied
+const SUCCESS_BUT_REQUIRES_RESTART: &str = "SuccessButRequiresRestart";
+
+/// A dependency of a plugin
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDependency {
+    /// Short name of the dependency
+    pub short_name: String,
+    /// Minimum version of the dependency
+    pub version: String,
+    /// Is the dependency optional
+    pub optional: bool,
+}
+
+/// A security warning affecting an installed plugin
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityWarning {
+    /// ID of the warning
+    pub id: String,
+    /// Message of the warning
+    pub message: Option<String>,
+    /// URL of the security advisory
+    pub url: Option<String>,
+    /// Is the warning active for the installed version
+    #[serde(default)]
+    pub active: bool,
+}
+
+/// A plugin installed on Jenkins
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Plugin {
+    /// Short name of the plugin, used to install it
+    pub short_name: String,
+    /// Display name of the plugin
+    pub long_name: Option<String>,
+    /// Installed version
+    pub version: String,
+    /// Is the plugin enabled
+    pub enabled: bool,
+    /// Is the plugin currently loaded
+    pub active: bool,
+    /// Is an update available
+    pub has_update: bool,
+    /// Is the plugin pinned
+    #[serde(default)]
+    pub pinned: bool,
+    /// Was the plugin uninstalled, pending a restart
+    #[serde(default)]
+    pub deleted: bool,
+    /// Version the plugin can be downgraded to
+    pub backup_version: Option<String>,
+    /// URL of the plugin
+    pub url: Option<String>,
+    /// Dependencies of the plugin
+    #[serde(default)]
+    pub dependencies: Vec<PluginDependency>,
+    /// Security warnings affecting the plugin. Only provided by recent versions of Jenkins
+    #[serde(default)]
+    pub security_warnings: Vec<SecurityWarning>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginManager {
+    plugins: Vec<Plugin>,
+}
+
+/// Status of an update center job
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallationStatus {
+    /// Type of status, like `Pending`, `Installing`, `Success` or `Failure`
+    #[serde(rename = "type")]
+    pub status_type: String,
+    /// Did the job succeed
+    pub success: Option<bool>,
+}
+
+/// A job of the update center, like a plugin installation
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCenterJob {
+    /// ID of the job
+    pub id: u32,
+    /// Type of job
+    #[serde(rename = "type")]
+    pub job_type: String,
+    /// Name of the plugin, for installation jobs
+    pub name: Option<String>,
+    /// Error message, if the job failed
+    pub error_message: Option<String>,
+    /// Status of the job
+    pub status: Option<InstallationStatus>,
+}
+
+/// Status of the update center
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCenterStatus {
+    /// Jobs of the update center
+    pub jobs: Vec<UpdateCenterJob>,
+    /// Is a restart required to complete the jobs
+    pub restart_required_for_completion: bool,
+}
+
+impl Jenkins {
+    /// Get the installed plugins
+    pub async fn get_plugins(&self) -> Result<Vec<Plugin>> {
+        let plugin_manager: PluginManager = self
+            .get_with_params(&Path::PluginManager, [("depth", "1")])
+            .await?
+            .json()
+            .await?;
+        Ok(plugin_manager.plugins)
+    }
+
+    /// Install plugins. Each plugin is specified as `name@version`, with `latest` as version
+    /// to install the latest one. Installation is asynchronous, its progress can be followed
+    /// with `update_center_status`
+    pub async fn install_plugins(&self, plugins: &[&str]) -> Result<()> {
+        let body = format!(
+            "<jenkins>{}</jenkins>",
+            plugins
+                .iter()
+                .map(|plugin| format!("<install plugin=\"{}\" />", escape_xml(plugin)))
+                .collect::<String>()
+        );
+        let _ = check_permission(
+            self.post_xml(&Path::InstallNecessaryPlugins, body, &[])
+                .await,
+            "Overall/Administer",
+        )?;
+        Ok(())
+    }
+
+    /// Refresh the update sites, to check for plugin updates
+    pub async fn check_for_plugin_updates(&self) -> Result<()> {
+        let _ = check_permission(
+            self.post(&Path::CheckUpdatesServer).await,
+            "Overall/Administer",
+        )?;
+        Ok(())
+    }
+
+    /// Get the status of the update center
+    pub async fn update_center_status(&self) -> Result<UpdateCenterStatus> {
+        Ok(self
+            .get_with_params(&Path::UpdateCenter, [("depth", "1")])
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// Get the short names of the plugins that need a restart to be installed, updated or
+    /// uninstalled
+    pub async fn get_plugins_requiring_restart(&self) -> Result<Vec<String>> {
+        let status = self.update_center_status().await?;
+        let mut plugins: Vec<String> = status
+            .jobs
+            .into_iter()
+            .filter(|job| {
+                job.status
+                    .as_ref()
+                    .is_some_and(|status| status.status_type == SUCCESS_BUT_REQUIRES_RESTART)
+            })
+            .filter_map(|job| job.name)
+            .collect();
+        plugins.extend(
+            self.get_plugins()
+                .await?
+                .into_iter()
+                .filter(|plugin| plugin.deleted)
+                .map(|plugin| plugin.short_name),
+        );
+        Ok(plugins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn can_get_plugins_requiring_restart() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/updateCenter/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"{"restartRequiredForCompletion": true, "jobs": [
+                    {"id": 1, "type": "ConnectionCheckJob", "errorMessage": null},
+                    {"id": 2, "type": "InstallationJob", "name": "git", "errorMessage": null,
+                     "status": {"type": "SuccessButRequiresRestart", "success": true}},
+                    {"id": 3, "type": "InstallationJob", "name": "junit", "errorMessage": null,
+                     "status": {"type": "Success", "success": true}}
+                ]}"#,
+            )
+            .create();
+        let _ = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::UrlEncoded("depth".into(), "1".into()))
+            .with_body(
+                r#"{"plugins": [
+                    {"shortName": "ant", "version": "1.0", "enabled": true, "active": true,
+                     "hasUpdate": false, "deleted": true, "dependencies": []},
+                    {"shortName": "junit", "version": "1.2", "enabled": true, "active": true,
+                     "hasUpdate": true, "deleted": false, "dependencies": [
+                        {"shortName": "ant", "version": "1.0", "optional": true}
+                     ]}
+                ]}"#,
+            )
+            .create();
+
+        let plugins = jenkins_client
+            .get_plugins_requiring_restart()
+            .await
+            .unwrap();
+
+        assert_eq!(plugins, vec!["git".to_string(), "ant".to_string()]);
+    }
+}
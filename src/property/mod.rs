@@ -4,6 +4,8 @@ use serde::{self, Deserialize, Serialize};
 
 use crate::helpers::Class;
 
+pub mod parameters;
+
 /// Trait implemented by specialization of property
 pub trait Property {}
 
@@ -46,3 +48,23 @@ impl Property for RateLimitBranchProperty {}
 pub struct BuildDiscarderProperty {}
 register_class!("jenkins.model.BuildDiscarderProperty" => BuildDiscarderProperty);
 impl Property for BuildDiscarderProperty {}
+
+/// Job has parameters
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParametersDefinitionProperty {
+    /// Definitions of the parameters
+    pub parameter_definitions: Vec<parameters::CommonParameterDefinition>,
+}
+register_class!("hudson.model.ParametersDefinitionProperty" => ParametersDefinitionProperty);
+impl Property for ParametersDefinitionProperty {}
+
+/// Email address of a user
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MailerUserProperty {
+    /// Email address
+    pub address: Option<String>,
+}
+register_class!("hudson.tasks.Mailer$UserProperty" => MailerUserProperty);
+impl Property for MailerUserProperty {}
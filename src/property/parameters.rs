@@ -0,0 +1,267 @@
+//! Types to parse the parameters definitions of a `Job`
+
+use serde::{self, Deserialize, Serialize};
+
+use crate::helpers::Class;
+
+use crate::action::parameters::{
+    BooleanParameterValue, CommonParameter, PasswordParameterValue, RunParameterValue,
+    StringParameterValue, TextParameterValue,
+};
+
+/// Trait implemented by specialization of ParameterDefinition
+pub trait ParameterDefinition {}
+
+/// A parameter definition of a `Job`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommonParameterDefinition {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Type of the parameter, as displayed by Jenkins
+    #[serde(rename = "type")]
+    pub parameter_type: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<CommonParameter>,
+
+    #[cfg(not(feature = "extra-fields-visibility"))]
+    #[serde(flatten)]
+    extra_fields: serde_json::Value,
+    #[cfg(feature = "extra-fields-visibility")]
+    /// Extra fields not parsed for a common object
+    #[serde(flatten)]
+    pub extra_fields: serde_json::Value,
+}
+specialize!(CommonParameterDefinition => ParameterDefinition);
+impl ParameterDefinition for CommonParameterDefinition {}
+
+/// A string parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StringParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<StringParameterValue>,
+    /// Are leading and trailing whitespaces removed from the value
+    #[serde(default)]
+    pub trim: bool,
+}
+register_class!("hudson.model.StringParameterDefinition" => StringParameterDefinition);
+impl ParameterDefinition for StringParameterDefinition {}
+
+/// A multi-line text parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TextParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<TextParameterValue>,
+    /// Are leading and trailing whitespaces removed from the value
+    #[serde(default)]
+    pub trim: bool,
+}
+register_class!("hudson.model.TextParameterDefinition" => TextParameterDefinition);
+impl ParameterDefinition for TextParameterDefinition {}
+
+/// A boolean parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BooleanParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<BooleanParameterValue>,
+}
+register_class!("hudson.model.BooleanParameterDefinition" => BooleanParameterDefinition);
+impl ParameterDefinition for BooleanParameterDefinition {}
+
+/// A choice parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChoiceParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter, the first choice
+    pub default_parameter_value: Option<StringParameterValue>,
+    /// Values allowed for the parameter
+    pub choices: Vec<String>,
+}
+register_class!("hudson.model.ChoiceParameterDefinition" => ChoiceParameterDefinition);
+impl ParameterDefinition for ChoiceParameterDefinition {}
+
+/// A password parameter definition. Jenkins never returns its default value
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<PasswordParameterValue>,
+}
+register_class!("hudson.model.PasswordParameterDefinition" => PasswordParameterDefinition);
+impl ParameterDefinition for PasswordParameterDefinition {}
+
+/// A file parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+}
+register_class!("hudson.model.FileParameterDefinition" => FileParameterDefinition);
+impl ParameterDefinition for FileParameterDefinition {}
+
+/// A run parameter definition, to select a build of another `Job`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<RunParameterValue>,
+    /// Name of the `Job` whose builds can be selected
+    pub project_name: String,
+    /// Filter on the builds that can be selected
+    pub filter: Option<String>,
+}
+register_class!("hudson.model.RunParameterDefinition" => RunParameterDefinition);
+impl ParameterDefinition for RunParameterDefinition {}
+
+/// A credentials parameter definition
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialsParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter, a credentials ID
+    pub default_parameter_value: Option<CommonParameter>,
+}
+register_class!("com.cloudbees.plugins.credentials.CredentialsParameterDefinition" => CredentialsParameterDefinition);
+impl ParameterDefinition for CredentialsParameterDefinition {}
+
+/// An extended choice parameter definition, from the extended choice parameter plugin
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedChoiceParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<CommonParameter>,
+    /// Values allowed for the parameter, separated by commas
+    pub value: Option<String>,
+    /// Default values of the parameter, separated by commas
+    pub default_value: Option<String>,
+    /// Delimiter between selected values when several can be selected
+    pub multi_select_delimiter: Option<String>,
+}
+register_class!("com.cwctravel.hudson.plugins.extended_choice_parameter.ExtendedChoiceParameterDefinition" => ExtendedChoiceParameterDefinition);
+impl ParameterDefinition for ExtendedChoiceParameterDefinition {}
+
+impl ExtendedChoiceParameterDefinition {
+    /// Values allowed for the parameter
+    pub fn choices(&self) -> Vec<&str> {
+        self.value
+            .as_deref()
+            .map(|value| value.split(',').map(str::trim).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A git parameter definition, from the git parameter plugin
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GitParameterDefinition {
+    /// The parameter name
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Default value of the parameter
+    pub default_parameter_value: Option<CommonParameter>,
+    /// Default value of the parameter, a branch, a tag or a revision
+    pub default_value: Option<String>,
+    /// Filter on the branches that can be selected
+    pub branch_filter: Option<String>,
+    /// Filter on the tags that can be selected
+    pub tag_filter: Option<String>,
+}
+register_class!("net.uaznia.lukanus.hudson.plugins.gitparameter.GitParameterDefinition" => GitParameterDefinition);
+impl ParameterDefinition for GitParameterDefinition {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{CommonProperty, ParametersDefinitionProperty};
+
+    #[test]
+    fn can_read_parameters_definitions() {
+        let property: CommonProperty = serde_json::from_str(
+            r#"{
+                "_class": "hudson.model.ParametersDefinitionProperty",
+                "parameterDefinitions": [
+                    {
+                        "_class": "hudson.model.ChoiceParameterDefinition",
+                        "defaultParameterValue": {
+                            "_class": "hudson.model.StringParameterValue",
+                            "name": "environment",
+                            "value": "staging"
+                        },
+                        "description": "where to deploy",
+                        "name": "environment",
+                        "type": "ChoiceParameterDefinition",
+                        "choices": ["staging", "production"]
+                    },
+                    {
+                        "_class": "hudson.model.FileParameterDefinition",
+                        "defaultParameterValue": null,
+                        "description": null,
+                        "name": "archive",
+                        "type": "FileParameterDefinition"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let definitions = property
+            .as_variant::<ParametersDefinitionProperty>()
+            .unwrap()
+            .parameter_definitions;
+        assert_eq!(definitions.len(), 2);
+
+        let choice = definitions[0]
+            .as_variant::<ChoiceParameterDefinition>()
+            .unwrap();
+        assert_eq!(choice.choices, vec!["staging", "production"]);
+        assert_eq!(choice.default_parameter_value.unwrap().value, "staging");
+        assert!(
+            definitions[1]
+                .as_variant::<FileParameterDefinition>()
+                .is_ok()
+        );
+    }
+}
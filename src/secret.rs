@@ -0,0 +1,66 @@
+//! A secret value, hidden when displayed
+
+use serde::{Deserialize, Serialize};
+
+/// A secret value, like an API token or a password. It is redacted when debug printed, and
+/// must be explicitly exposed to be used
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+impl Secret {
+    /// Wrap a secret value
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    /// Get the secret value
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("Secret").field(&"***").finish()
+    }
+}
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+/// Secret binary content, like a secret file. It is redacted when debug printed, and must be
+/// explicitly exposed to be used
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretBytes(Vec<u8>);
+impl SecretBytes {
+    /// Wrap a secret content
+    pub fn new(value: impl Into<Vec<u8>>) -> Self {
+        SecretBytes(value.into())
+    }
+
+    /// Get the secret content
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+}
+impl std::fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("SecretBytes").field(&"***").finish()
+    }
+}
+impl From<Vec<u8>> for SecretBytes {
+    fn from(value: Vec<u8>) -> Self {
+        SecretBytes(value)
+    }
+}
+impl From<&[u8]> for SecretBytes {
+    fn from(value: &[u8]) -> Self {
+        SecretBytes(value.to_vec())
+    }
+}
@@ -2,6 +2,13 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::Jenkins;
+use crate::client::{self, Result};
+use crate::client_internals::{DescriptorResponse, Name, Path, check_permission};
+use crate::job::ShortJob;
+use crate::property::{CommonProperty, MailerUserProperty};
+use crate::secret::Secret;
+
 /// Short User that is used in list and links from other structs
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,3 +26,362 @@ pub struct ShortUser {
     #[serde(flatten)]
     pub extra_fields: Option<serde_json::Value>,
 }
+impl ShortUser {
+    /// Get the full details of a `User` matching the `ShortUser`
+    pub async fn get_full_user(&self, jenkins_client: &Jenkins) -> Result<User> {
+        let path = jenkins_client.url_to_path(&self.absolute_url);
+        if let Path::User { .. } = path {
+            return Ok(jenkins_client.get(&path).await?.json().await?);
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.absolute_url.clone(),
+            expected: client::error::ExpectedType::User,
+        }
+        .into())
+    }
+}
+
+/// Helper type to act on a user
+#[derive(Debug)]
+pub struct UserId<'a>(pub &'a str);
+impl<'a> From<&'a str> for UserId<'a> {
+    fn from(v: &'a str) -> UserId<'a> {
+        UserId(v)
+    }
+}
+impl<'a> From<&'a String> for UserId<'a> {
+    fn from(v: &'a String) -> UserId<'a> {
+        UserId(v)
+    }
+}
+impl<'a> From<&'a User> for UserId<'a> {
+    fn from(v: &'a User) -> UserId<'a> {
+        UserId(&v.id)
+    }
+}
+
+/// A Jenkins user
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    /// ID of the user
+    pub id: String,
+    /// Full name of the user
+    pub full_name: String,
+    /// Absolute URL to the user profile
+    pub absolute_url: String,
+    /// Description of the user
+    pub description: Option<String>,
+    /// Properties of the user
+    pub property: Vec<CommonProperty>,
+
+    #[cfg(not(feature = "extra-fields-visibility"))]
+    #[serde(flatten)]
+    pub(crate) extra_fields: Option<serde_json::Value>,
+    #[cfg(feature = "extra-fields-visibility")]
+    /// Extra fields not parsed for a common object
+    #[serde(flatten)]
+    pub extra_fields: Option<serde_json::Value>,
+}
+impl User {
+    /// Email address of the user, if known
+    pub fn email(&self) -> Option<String> {
+        self.property
+            .iter()
+            .find_map(|property| property.as_variant::<MailerUserProperty>().ok())
+            .and_then(|property| property.address)
+    }
+}
+
+/// The current user, as authenticated by Jenkins
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WhoAmI {
+    /// Name of the user
+    pub name: String,
+    /// Is the user anonymous
+    pub anonymous: bool,
+    /// Is the user authenticated
+    pub authenticated: bool,
+    /// Authorities granted to the user
+    pub authorities: Vec<String>,
+    /// Details of the authentication
+    pub details: Option<String>,
+}
+
+/// A user known by Jenkins, with their last activity
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    /// The user
+    pub user: ShortUser,
+    /// When the user was last seen in a build, in milliseconds since epoch
+    pub last_change: Option<u64>,
+    /// Job of the last build the user was seen in
+    pub project: Option<ShortJob>,
+}
+
+#[derive(Debug, Deserialize)]
+struct People {
+    users: Vec<Person>,
+}
+
+/// An API token, newly generated
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedApiToken {
+    /// Name of the token
+    #[serde(rename = "tokenName")]
+    pub name: String,
+    /// UUID of the token, used to revoke it
+    #[serde(rename = "tokenUuid")]
+    pub uuid: String,
+    /// Value of the token. Jenkins only provides it when generating the token
+    #[serde(rename = "tokenValue")]
+    pub value: Secret,
+}
+
+/// An API token of a user
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    /// Name of the token
+    pub name: String,
+    /// UUID of the token, used to revoke it
+    pub uuid: String,
+    /// When the token was created, in milliseconds since epoch
+    pub creation_date: Option<u64>,
+    /// When the token was last used, in milliseconds since epoch
+    pub last_use_date: Option<u64>,
+    /// Number of times the token was used
+    pub use_counter: u32,
+    /// Is this a legacy token, created before Jenkins 2.129
+    pub legacy: bool,
+}
+
+/// Quote a string for a Groovy script
+fn groovy_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+impl Jenkins {
+    /// Get the user currently authenticated
+    pub async fn who_am_i(&self) -> Result<WhoAmI> {
+        Ok(self.get(&Path::WhoAmI).await?.json().await?)
+    }
+
+    /// Get a `User`
+    pub async fn get_user<'a, U>(&self, user_id: U) -> Result<User>
+    where
+        U: Into<UserId<'a>>,
+    {
+        Ok(self
+            .get(&Path::User {
+                id: Name::Name(user_id.into().0),
+            })
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// List the users known by Jenkins, with their last activity
+    pub async fn list_people(&self) -> Result<Vec<Person>> {
+        let people: People = self
+            .get_with_params(
+                &Path::AsynchPeople,
+                [(
+                    "tree",
+                    "users[lastChange,project[name,url,color],user[fullName,absoluteUrl,id]]",
+                )],
+            )
+            .await?
+            .json()
+            .await?;
+        Ok(people.users)
+    }
+
+    /// Generate a new API token named `token_name` for the user `user_id`
+    pub async fn generate_api_token<'a, U>(
+        &self,
+        user_id: U,
+        token_name: &str,
+    ) -> Result<GeneratedApiToken>
+    where
+        U: Into<UserId<'a>>,
+    {
+        let user_id = user_id.into().0;
+        let body = serde_urlencoded::to_string([("newTokenName", token_name)])?;
+        let response: DescriptorResponse<GeneratedApiToken> = check_permission(
+            self.post_with_body(
+                &Path::GenerateApiToken {
+                    user_id: Name::Name(user_id),
+                },
+                body,
+                &[],
+            )
+            .await,
+            "User/Configure",
+        )?
+        .json()
+        .await?;
+        response.into_result()?.ok_or_else(|| {
+            client::Error::ErrorResponse {
+                message: "no token generated".to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// List the API tokens of the user `user_id`
+    ///
+    /// Jenkins doesn't provide the tokens through its API, not even to their user, so they are
+    /// read with the script console. This requires the `Overall/RunScripts` permission, given
+    /// to administrators, and fails if the script console is disabled
+    ///
+    /// # Errors
+    /// Without the permission, this will return an
+    /// [`Error::MissingPermission`](../client/enum.Error.html#variant.MissingPermission)
+    pub async fn list_api_tokens<'a, U>(&self, user_id: U) -> Result<Vec<ApiToken>>
+    where
+        U: Into<UserId<'a>>,
+    {
+        let script = format!(
+            r#"def user = hudson.model.User.getById({}, false)
+def property = user?.getProperty(jenkins.security.ApiTokenProperty)
+println groovy.json.JsonOutput.toJson((property?.tokenList ?: []).collect {{ [
+    name: it.name,
+    uuid: it.uuid,
+    creationDate: it.creationDate?.time,
+    lastUseDate: it.lastUseDate?.time,
+    useCounter: it.useCounter,
+    legacy: it.isLegacy,
+] }})"#,
+            groovy_string(user_id.into().0)
+        );
+        Ok(serde_json::from_str(&self.run_script(&script).await?)?)
+    }
+
+    /// Revoke the API token with UUID `token_uuid` of the user `user_id`
+    pub async fn revoke_api_token<'a, U>(&self, user_id: U, token_uuid: &str) -> Result<()>
+    where
+        U: Into<UserId<'a>>,
+    {
+        let body = serde_urlencoded::to_string([("tokenUuid", token_uuid)])?;
+        let response: DescriptorResponse<serde_json::Value> = check_permission(
+            self.post_with_body(
+                &Path::RevokeApiToken {
+                    user_id: Name::Name(user_id.into().0),
+                },
+                body,
+                &[],
+            )
+            .await,
+            "User/Configure",
+        )?
+        .json()
+        .await?;
+        let _ = response.into_result()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_get_full_user() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/user/jane.doe/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"{
+                    "_class": "hudson.model.User",
+                    "absoluteUrl": "http://localhost:8080/user/jane.doe",
+                    "description": null,
+                    "fullName": "Jane Doe",
+                    "id": "jane.doe",
+                    "property": [
+                        {"_class": "jenkins.security.ApiTokenProperty"},
+                        {"_class": "hudson.tasks.Mailer$UserProperty", "address": "jane@example.com"}
+                    ]
+                }"#,
+            )
+            .create();
+
+        let short_user: ShortUser = serde_json::from_str(&format!(
+            r#"{{"fullName": "Jane Doe", "absoluteUrl": "{}/user/jane.doe"}}"#,
+            s.url()
+        ))
+        .unwrap();
+        let user = short_user.get_full_user(&jenkins_client).await.unwrap();
+
+        assert_eq!(user.id, "jane.doe");
+        assert_eq!(user.email().as_deref(), Some("jane@example.com"));
+    }
+
+    #[tokio::test]
+    async fn listing_api_tokens_requires_run_scripts() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s.mock("POST", "/scriptText").with_status(403).create();
+
+        let tokens = jenkins_client.list_api_tokens("jane.doe").await;
+
+        assert_eq!(
+            format!("{tokens:?}"),
+            r#"Err(MissingPermission { permission: "Overall/RunScripts" })"#
+        );
+    }
+
+    #[tokio::test]
+    async fn can_generate_api_token() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock(
+                "POST",
+                "/user/service/descriptorByName/jenkins.security.ApiTokenProperty/generateNewToken",
+            )
+            .match_body("newTokenName=ci")
+            .with_body(
+                r#"{"status": "ok", "data": {
+                    "tokenName": "ci",
+                    "tokenUuid": "1c7b2a8e-0c4a-4bd3-9c2e-5e4f0e0d9a10",
+                    "tokenValue": "11aabbccddeeff"
+                }}"#,
+            )
+            .create();
+
+        let token = jenkins_client
+            .generate_api_token("service", "ci")
+            .await
+            .unwrap();
+
+        assert_eq!(token.uuid, "1c7b2a8e-0c4a-4bd3-9c2e-5e4f0e0d9a10");
+        assert_eq!(token.value.expose(), "11aabbccddeeff");
+        assert!(!format!("{:?}", token).contains("11aabbccddeeff"));
+    }
+
+    #[test]
+    fn can_quote_groovy_string() {
+        assert_eq!(groovy_string(r"o'neil\"), r"'o\'neil\\'");
+    }
+}
@@ -0,0 +1,481 @@
+//! Version of Jenkins, and the capabilities it provides
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::Jenkins;
+use crate::client::{self, Result};
+use crate::client_internals::Path;
+
+/// Header with the version of Jenkins, set once Jenkins is fully up
+pub(crate) const VERSION_HEADER: &str = "X-Jenkins";
+/// Header with the version of Hudson, set by old versions
+const HUDSON_VERSION_HEADER: &str = "X-Hudson";
+/// Header with the public key identifying this instance
+const INSTANCE_IDENTITY_HEADER: &str = "X-Instance-Identity";
+
+/// Plugin providing the `wfapi` endpoints for pipelines
+const WORKFLOW_API_PLUGIN: &str = "pipeline-rest-api";
+/// Plugin providing the Blue Ocean REST API
+const BLUE_OCEAN_PLUGIN: &str = "blueocean-rest";
+
+/// Version of Jenkins, like `2.440` for a weekly release or `2.426.3` for a LTS release
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JenkinsVersion {
+    /// Major version
+    pub major: u32,
+    /// Minor version
+    pub minor: u32,
+    /// Patch version, only set for LTS releases
+    pub patch: Option<u32>,
+    /// Qualifier of the version, like `SNAPSHOT`
+    pub qualifier: Option<String>,
+}
+impl JenkinsVersion {
+    /// Create a version
+    pub fn new(major: u32, minor: u32, patch: Option<u32>) -> Self {
+        JenkinsVersion {
+            major,
+            minor,
+            patch,
+            qualifier: None,
+        }
+    }
+}
+impl FromStr for JenkinsVersion {
+    type Err = client::Error;
+
+    fn from_str(version: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || client::Error::InvalidVersion {
+            version: version.to_string(),
+        };
+        let (numbers, qualifier) = match version.trim().split_once('-') {
+            Some((numbers, qualifier)) => (numbers, Some(qualifier.to_string())),
+            None => (version.trim(), None),
+        };
+        let mut numbers = numbers.split('.').map(u32::from_str);
+        let major = numbers.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let minor = numbers.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let patch = numbers.next().transpose().map_err(|_| invalid())?;
+        if numbers.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(JenkinsVersion {
+            major,
+            minor,
+            patch,
+            qualifier,
+        })
+    }
+}
+impl std::fmt::Display for JenkinsVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        if let Some(qualifier) = &self.qualifier {
+            write!(f, "-{qualifier}")?;
+        }
+        Ok(())
+    }
+}
+impl Ord for JenkinsVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch.unwrap_or(0))
+            .cmp(&(other.major, other.minor, other.patch.unwrap_or(0)))
+            // a version with a qualifier is a pre-release of the version without
+            .then_with(|| match (&self.qualifier, &other.qualifier) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(this), Some(other)) => this.cmp(other),
+            })
+    }
+}
+impl PartialOrd for JenkinsVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A feature that Jenkins may not provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// The `wfapi` endpoints for pipelines, from the `pipeline-rest-api` plugin
+    WorkflowApi,
+    /// The Blue Ocean REST API, from the `blueocean-rest` plugin
+    BlueOcean,
+}
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Capability::WorkflowApi => write!(f, "pipeline REST API ({WORKFLOW_API_PLUGIN})"),
+            Capability::BlueOcean => write!(f, "Blue Ocean ({BLUE_OCEAN_PLUGIN})"),
+        }
+    }
+}
+
+/// What a Jenkins instance provides. The capabilities provided by plugins are `None` if
+/// the plugins can't be listed, which requires the `Overall/Administer` permission
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// Version of Jenkins
+    pub version: JenkinsVersion,
+    /// Public key identifying this instance, if provided
+    pub instance_identity: Option<String>,
+    /// Does Jenkins require a crumb for CSRF protection. The client only requests crumbs
+    /// when it does
+    pub crumb_required: bool,
+    /// Are the `wfapi` endpoints available
+    pub workflow_api: Option<bool>,
+    /// Is the Blue Ocean REST API available
+    pub blue_ocean: Option<bool>,
+}
+impl Capabilities {
+    fn provides(&self, capability: Capability) -> Option<bool> {
+        match capability {
+            Capability::WorkflowApi => self.workflow_api,
+            Capability::BlueOcean => self.blue_ocean,
+        }
+    }
+
+    /// Check if a capability is known to be provided
+    pub fn has(&self, capability: Capability) -> bool {
+        self.provides(capability) == Some(true)
+    }
+
+    /// Fail with an
+    /// [`Error::MissingCapability`](../client/enum.Error.html#variant.MissingCapability) if a
+    /// capability is known not to be provided
+    pub fn require(&self, capability: Capability) -> Result<()> {
+        if self.provides(capability) == Some(false) {
+            Err(client::Error::MissingCapability { capability }.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UseCrumbs {
+    use_crumbs: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivePlugin {
+    short_name: String,
+    active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActivePlugins {
+    plugins: Vec<ActivePlugin>,
+}
+
+impl Jenkins {
+    /// Query the headers of `Home` and whether it requires crumbs, caching the version and
+    /// the use of crumbs. Another call may have cached them concurrently, they are the same.
+    /// Versions of Jenkins that don't tell whether they use crumbs are considered to use them
+    async fn query_home(&self) -> Result<(reqwest::header::HeaderMap, bool)> {
+        let response = self
+            .get_with_params(&Path::Home, [("tree", "useCrumbs")])
+            .await?;
+        let headers = response.headers().clone();
+        let home: UseCrumbs = response.json().await?;
+        if let Ok(version) = Self::version_from_headers(&headers) {
+            let _ = self.version.set(version);
+        }
+        let use_crumbs = home.use_crumbs.unwrap_or(true);
+        let _ = self.crumbs_required.set(use_crumbs);
+        Ok((headers, use_crumbs))
+    }
+
+    /// Get the version of Jenkins. It is only queried once, then cached by the client
+    pub async fn version(&self) -> Result<JenkinsVersion> {
+        if let Some(version) = self.version.get() {
+            return Ok(version.clone());
+        }
+        let (headers, _) = self.query_home().await?;
+        Self::version_from_headers(&headers)
+    }
+
+    /// Does Jenkins require a crumb for CSRF protection. It is only queried once, then cached
+    /// by the client. If Jenkins can't tell, like for users without the `Overall/Read`
+    /// permission, a crumb is required
+    pub(crate) async fn crumbs_required(&self) -> bool {
+        match self.crumbs_required.get() {
+            Some(required) => *required,
+            None => match self.query_home().await {
+                Ok((_, required)) => required,
+                Err(_) => *self.crumbs_required.get_or_init(|| true),
+            },
+        }
+    }
+
+    /// Fail with an
+    /// [`Error::MissingCapability`](../client/enum.Error.html#variant.MissingCapability) if
+    /// Jenkins is known not to provide a capability. The capabilities are cached by the client
+    pub(crate) async fn require(&self, capability: Capability) -> Result<()> {
+        self.capabilities().await?.require(capability)
+    }
+
+    fn version_from_headers(headers: &reqwest::header::HeaderMap) -> Result<JenkinsVersion> {
+        let header = headers
+            .get(VERSION_HEADER)
+            .or_else(|| headers.get(HUDSON_VERSION_HEADER))
+            .ok_or_else(|| client::Error::InvalidVersion {
+                version: String::new(),
+            })?;
+        Ok(header.to_str()?.parse()?)
+    }
+
+    /// Get the `Capabilities` of Jenkins, from its version, configuration and installed plugins.
+    /// They are only queried once, then cached by the client
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        let cached = self
+            .capabilities
+            .read()
+            .ok()
+            .and_then(|capabilities| capabilities.clone());
+        match cached {
+            Some(capabilities) => Ok(capabilities),
+            None => self.refresh_capabilities().await,
+        }
+    }
+
+    /// Query the `Capabilities` of Jenkins again, like after installing plugins, and cache them
+    pub async fn refresh_capabilities(&self) -> Result<Capabilities> {
+        let (headers, use_crumbs) = self.query_home().await?;
+        let version = Self::version_from_headers(&headers)?;
+        let instance_identity = headers
+            .get(INSTANCE_IDENTITY_HEADER)
+            .and_then(|header| header.to_str().ok())
+            .map(ToString::to_string);
+
+        // listing the plugins requires to be an administrator, other users can't know
+        // which are installed
+        let plugins: Option<ActivePlugins> = match self
+            .get_with_params(
+                &Path::PluginManager,
+                [("tree", "plugins[shortName,active]")],
+            )
+            .await
+        {
+            Ok(response) => Some(response.json().await?),
+            Err(err)
+                if err
+                    .downcast_ref::<reqwest::Error>()
+                    .and_then(reqwest::Error::status)
+                    == Some(reqwest::StatusCode::FORBIDDEN) =>
+            {
+                None
+            }
+            Err(err) => return Err(err),
+        };
+        let is_active = |name: &str| {
+            plugins.as_ref().map(|plugins| {
+                plugins
+                    .plugins
+                    .iter()
+                    .any(|plugin| plugin.active && plugin.short_name == name)
+            })
+        };
+
+        let capabilities = Capabilities {
+            version,
+            instance_identity,
+            crumb_required: use_crumbs,
+            workflow_api: is_active(WORKFLOW_API_PLUGIN),
+            blue_ocean: is_active(BLUE_OCEAN_PLUGIN),
+        };
+        if let Ok(mut cached) = self.capabilities.write() {
+            *cached = Some(capabilities.clone());
+        }
+        Ok(capabilities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_and_compare_versions() {
+        let lts: JenkinsVersion = "2.426.3".parse().unwrap();
+        let weekly: JenkinsVersion = "2.440".parse().unwrap();
+        let snapshot: JenkinsVersion = "2.440-SNAPSHOT".parse().unwrap();
+
+        assert_eq!(lts, JenkinsVersion::new(2, 426, Some(3)));
+        assert!(lts < weekly);
+        assert!(snapshot < weekly);
+        assert_eq!(snapshot.to_string(), "2.440-SNAPSHOT");
+        assert!("2.x".parse::<JenkinsVersion>().is_err());
+        assert!("2".parse::<JenkinsVersion>().is_err());
+    }
+
+    #[tokio::test]
+    async fn version_is_cached() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": true}"#)
+            .expect(1)
+            .create();
+
+        let version = jenkins_client.version().await.unwrap();
+        assert_eq!(version, JenkinsVersion::new(2, 426, Some(3)));
+        let _ = jenkins_client.version().await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn plugins_are_unknown_without_permission() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": false}"#)
+            .create();
+        let _ = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+
+        let capabilities = jenkins_client.capabilities().await.unwrap();
+
+        assert_eq!(capabilities.blue_ocean, None);
+        assert!(!capabilities.has(Capability::BlueOcean));
+        assert!(capabilities.require(Capability::BlueOcean).is_ok());
+        assert!(!jenkins_client.crumbs_required().await);
+    }
+
+    #[tokio::test]
+    async fn capabilities_are_cached_until_refreshed() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": true}"#)
+            .create();
+        let before = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"plugins": []}"#)
+            .expect(1)
+            .create();
+        let _ = s
+            .mock("GET", "/pluginManager/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"plugins": [{"shortName": "blueocean-rest", "active": true}]}"#)
+            .create();
+
+        assert!(
+            !jenkins_client
+                .capabilities()
+                .await
+                .unwrap()
+                .has(Capability::BlueOcean)
+        );
+        assert!(
+            !jenkins_client
+                .capabilities()
+                .await
+                .unwrap()
+                .has(Capability::BlueOcean)
+        );
+        before.assert();
+        assert!(
+            jenkins_client
+                .refresh_capabilities()
+                .await
+                .unwrap()
+                .has(Capability::BlueOcean)
+        );
+        assert!(
+            jenkins_client
+                .capabilities()
+                .await
+                .unwrap()
+                .has(Capability::BlueOcean)
+        );
+    }
+
+    #[tokio::test]
+    async fn crumb_is_only_requested_when_required() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("X-Jenkins", "2.426.3")
+            .with_body(r#"{"useCrumbs": false}"#)
+            .create();
+        let crumb = s
+            .mock("GET", "/crumbIssuer/api/json")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create();
+        let _ = s.mock("POST", "/quietDown").create();
+
+        jenkins_client.quiet_down(None).await.unwrap();
+
+        crumb.assert();
+    }
+
+    #[tokio::test]
+    async fn crumb_is_requested_when_jenkins_does_not_tell() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url()).build().unwrap();
+
+        let _ = s
+            .mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+        let crumb = s
+            .mock("GET", "/crumbIssuer/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"crumb": "abc", "crumbRequestField": "Jenkins-Crumb"}"#)
+            .expect(1)
+            .create();
+        let _ = s
+            .mock("POST", "/quietDown")
+            .match_header("jenkins-crumb", "abc")
+            .create();
+
+        jenkins_client.quiet_down(None).await.unwrap();
+
+        crumb.assert();
+    }
+}
@@ -1,19 +1,47 @@
 //! Jenkins Views, use to group Jobs
 
+use std::marker::PhantomData;
+use std::sync::LazyLock;
+
+use regex::Regex;
 use serde::{self, Deserialize, Serialize};
 
-use crate::helpers::Class;
+use crate::helpers::{Class, escape_xml};
 
 use crate::Jenkins;
 use crate::client::{self, Result};
-use crate::client_internals::{Name, Path};
+use crate::client_internals::{Name, Path, check_permission};
 use crate::job::{JobName, ShortJob};
 use crate::property::CommonProperty;
 
+/// The include regex of a `ListView` in its config.xml
+static INCLUDE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\s*<includeRegex>.*?</includeRegex>").unwrap());
+
+/// Get the path for an action on the view at `url`, keeping the folders and views it is in
+fn view_action_path<'a, F>(jenkins_client: &Jenkins, url: &'a str, action: F) -> Result<Path<'a>>
+where
+    F: FnOnce(Name<'a>) -> Path<'a>,
+{
+    jenkins_client
+        .url_to_path(url)
+        .map_innermost(|path| match path {
+            Path::View { name } => Some(action(name)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            client::Error::InvalidUrl {
+                url: url.to_string(),
+                expected: client::error::ExpectedType::View,
+            }
+            .into()
+        })
+}
+
 /// Short View that is used in lists and links from other structs
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ShortView {
+pub struct ShortView<T: View = CommonView> {
     /// Name of the view
     pub name: String,
     /// URL for the view
@@ -26,20 +54,39 @@ pub struct ShortView {
     /// Extra fields not parsed for a common object
     #[serde(flatten)]
     pub extra_fields: Option<serde_json::Value>,
+
+    #[serde(skip)]
+    view_type: PhantomData<T>,
 }
 
-impl ShortView {
+impl<T> ShortView<T>
+where
+    T: View,
+    for<'de> T: Deserialize<'de>,
+{
     /// Get the full details of a `View` matching the `ShortView`
-    pub async fn get_full_view(&self, jenkins_client: &Jenkins) -> Result<CommonView> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { .. } = path {
-            Ok(jenkins_client.get(&path).await?.json().await?)
-        } else {
-            Err(client::Error::InvalidUrl {
-                url: self.url.clone(),
-                expected: client::error::ExpectedType::View,
-            }
-            .into())
+    pub async fn get_full_view(&self, jenkins_client: &Jenkins) -> Result<T> {
+        let path = view_action_path(jenkins_client, &self.url, |name| Path::View { name })?;
+        Ok(jenkins_client.get(&path).await?.json().await?)
+    }
+}
+
+/// Type of a view to create
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewKind {
+    /// A view listing jobs, selected by name or by regular expression
+    ListView,
+    /// A view showing the jobs the current user has access to
+    MyView,
+    /// A view grouping other views. Requires the `nested-view` plugin
+    NestedView,
+}
+impl ViewKind {
+    fn class(self) -> &'static str {
+        match self {
+            ViewKind::ListView => ListView::with_class(),
+            ViewKind::MyView => MyView::with_class(),
+            ViewKind::NestedView => NestedView::with_class(),
         }
     }
 }
@@ -69,9 +116,55 @@ impl<'a, T: View> From<&'a T> for ViewName<'a> {
 }
 
 /// Trait implemented by specialization of view
-pub trait View {
+pub trait View: Sync {
     /// Get the name of the view
     fn name(&self) -> &str;
+
+    /// Get the url of the view
+    fn url(&self) -> &str;
+
+    /// Delete this view
+    fn delete(&self, jenkins_client: &Jenkins) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let path =
+                view_action_path(jenkins_client, self.url(), |name| Path::ViewDelete { name })?;
+            let _ = check_permission(jenkins_client.post(&path).await, "View/Delete")?;
+            Ok(())
+        }
+    }
+
+    /// Get the config.xml file for this view
+    fn get_config_xml(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let path = view_action_path(jenkins_client, self.url(), |name| Path::ViewConfigXML {
+                name,
+            })?;
+            Ok(jenkins_client.get_blob(&path).await?.text().await?)
+        }
+    }
+
+    /// Replace the config.xml file for this view
+    fn update_config_xml(
+        &self,
+        jenkins_client: &Jenkins,
+        config_xml: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let path = view_action_path(jenkins_client, self.url(), |name| Path::ViewConfigXML {
+                name,
+            })?;
+            let _ = check_permission(
+                jenkins_client
+                    .post_xml(&path, config_xml.to_string(), &[])
+                    .await,
+                "View/Configure",
+            )?;
+            Ok(())
+        }
+    }
 }
 
 /// A Jenkins `View` with a list of `ShortJob`
@@ -105,28 +198,162 @@ impl View for CommonView {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
 }
 
-/// A Jenkins `View` with a list of `ShortJob`
+macro_rules! view_with_common_fields_and_impl {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field:ident: $field_type:ty,
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        pub struct $name {
+            /// Description of the view
+            pub description: Option<String>,
+            /// Name of the view
+            pub name: String,
+            /// URL for the view
+            pub url: String,
+            /// List of jobs in the view
+            #[serde(default)]
+            pub jobs: Vec<ShortJob>,
+            /// Properties of the view
+            #[serde(default)]
+            pub property: Vec<CommonProperty>,
+            $(
+                $(#[$field_attr])*
+                pub $field: $field_type,
+            )*
+        }
+        impl View for $name {
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn url(&self) -> &str {
+                &self.url
+            }
+        }
+    };
+}
+
+view_with_common_fields_and_impl!(
+    /// A Jenkins `View` with a list of `ShortJob`
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ListView {}
+);
+register_class!("hudson.model.ListView" => ListView);
+
+view_with_common_fields_and_impl!(
+    /// The `View` with all the jobs
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AllView {}
+);
+register_class!("hudson.model.AllView" => AllView);
+
+view_with_common_fields_and_impl!(
+    /// A `View` with the jobs the current user has access to
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MyView {}
+);
+register_class!("hudson.model.MyView" => MyView);
+
+view_with_common_fields_and_impl!(
+    /// A `View` grouping other views
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct NestedView {
+        /// Views in this view
+        pub views: Vec<ShortView>,
+    }
+);
+register_class!("hudson.plugins.nested_view.NestedView" => NestedView);
+
+view_with_common_fields_and_impl!(
+    /// A `View` with portlets, from the `dashboard-view` plugin
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DashboardView {
+        /// Is the standard list of jobs shown above the portlets
+        #[serde(default)]
+        pub include_std_job_list: bool,
+        /// Portlets shown at the top of the view
+        #[serde(default)]
+        pub top_portlets: Vec<DashboardPortlet>,
+        /// Portlets shown on the left of the view
+        #[serde(default)]
+        pub left_portlets: Vec<DashboardPortlet>,
+        /// Portlets shown on the right of the view
+        #[serde(default)]
+        pub right_portlets: Vec<DashboardPortlet>,
+        /// Portlets shown at the bottom of the view
+        #[serde(default)]
+        pub bottom_portlets: Vec<DashboardPortlet>,
+    }
+);
+register_class!("hudson.plugins.view.dashboard.Dashboard" => DashboardView);
+
+/// A portlet of a `DashboardView`
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ListView {
-    /// Description of the view
-    pub description: Option<String>,
-    /// Name of the view
-    pub name: String,
-    /// URL for the view
-    pub url: String,
-    /// List of jobs in the view
-    pub jobs: Vec<ShortJob>,
-    /// Properties of the view
-    pub property: Vec<CommonProperty>,
+pub struct DashboardPortlet {
+    /// _class provided by Jenkins, the kind of portlet
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the portlet
+    pub name: Option<String>,
 }
-register_class!("hudson.model.ListView" => ListView);
-impl View for ListView {
-    fn name(&self) -> &str {
-        &self.name
+
+view_with_common_fields_and_impl!(
+    /// A `View` of upstream and downstream jobs, from the `build-pipeline-plugin` plugin
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BuildPipelineView {
+        /// Name of the first job of the pipeline
+        pub selected_job: Option<String>,
+    }
+);
+register_class!(
+    "au.com.centrumsystems.hudson.plugin.buildpipeline.BuildPipelineView" => BuildPipelineView
+);
+
+view_with_common_fields_and_impl!(
+    /// A `View` split in sections, from the `sectioned-view` plugin
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SectionedView {
+        /// Sections of the view
+        #[serde(default)]
+        pub sections: Vec<SectionedViewSection>,
     }
+);
+register_class!("hudson.plugins.sectioned_view.SectionedView" => SectionedView);
+
+/// A section of a `SectionedView`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionedViewSection {
+    /// _class provided by Jenkins, the kind of section
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the section
+    pub name: Option<String>,
+    /// Names of the jobs selected for the section
+    #[serde(default)]
+    pub job_names: Vec<String>,
+    /// Regular expression selecting jobs for the section
+    pub include_regex: Option<String>,
 }
 
 impl ListView {
@@ -135,22 +362,12 @@ impl ListView {
     where
         J: Into<JobName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { name } = path {
-            let _ = jenkins_client
-                .post(&Path::AddJobToView {
-                    job_name: Name::Name(job_name.into().0),
-                    view_name: name,
-                })
-                .await?;
-            Ok(())
-        } else {
-            Err(client::Error::InvalidUrl {
-                url: self.url.clone(),
-                expected: client::error::ExpectedType::View,
-            }
-            .into())
-        }
+        let path = view_action_path(jenkins_client, &self.url, |name| Path::AddJobToView {
+            job_name: Name::Name(job_name.into().0),
+            view_name: name,
+        })?;
+        let _ = jenkins_client.post(&path).await?;
+        Ok(())
     }
 
     /// Remove the job `job_name` from this view
@@ -158,22 +375,64 @@ impl ListView {
     where
         J: Into<JobName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { name } = path {
-            let _ = jenkins_client
-                .post(&Path::RemoveJobFromView {
-                    job_name: Name::Name(job_name.into().0),
-                    view_name: name,
-                })
-                .await?;
-            Ok(())
-        } else {
-            Err(client::Error::InvalidUrl {
-                url: self.url.clone(),
-                expected: client::error::ExpectedType::View,
+        let path = view_action_path(jenkins_client, &self.url, |name| Path::RemoveJobFromView {
+            job_name: Name::Name(job_name.into().0),
+            view_name: name,
+        })?;
+        let _ = jenkins_client.post(&path).await?;
+        Ok(())
+    }
+
+    /// Set the regular expression selecting the jobs of this view, or remove it with `None`
+    pub async fn set_include_regex(
+        &self,
+        jenkins_client: &Jenkins,
+        include_regex: Option<&str>,
+    ) -> Result<()> {
+        let config_xml = self.get_config_xml(jenkins_client).await?;
+        let mut config_xml = INCLUDE_REGEX.replace(&config_xml, "").into_owned();
+        if let Some(include_regex) = include_regex {
+            let end = config_xml.rfind("</").unwrap_or(config_xml.len());
+            config_xml.insert_str(
+                end,
+                &format!(
+                    "  <includeRegex>{}</includeRegex>\n",
+                    escape_xml(include_regex)
+                ),
+            );
+        }
+        self.update_config_xml(jenkins_client, &config_xml).await
+    }
+}
+
+impl NestedView {
+    /// Get all the views in this view and in its nested views, depth first
+    pub async fn get_all_views(&self, jenkins_client: &Jenkins) -> Result<Vec<CommonView>> {
+        let mut views = vec![];
+        let mut to_visit: Vec<ShortView> = self.views.iter().rev().cloned().collect();
+        while let Some(short_view) = to_visit.pop() {
+            let view = short_view.get_full_view(jenkins_client).await?;
+            if view.class.as_deref() == Some(NestedView::with_class()) {
+                let nested_view: NestedView = view.as_variant()?;
+                to_visit.extend(nested_view.views.into_iter().rev());
             }
-            .into())
+            views.push(view);
         }
+        Ok(views)
+    }
+
+    /// Create a view in this view
+    pub async fn create_view(
+        &self,
+        jenkins_client: &Jenkins,
+        name: &str,
+        kind: ViewKind,
+    ) -> Result<()> {
+        let path = view_action_path(jenkins_client, &self.url, |view_name| Path::InView {
+            view_name,
+            path: Box::new(Path::CreateView),
+        })?;
+        jenkins_client.create_view_at(&path, name, kind).await
     }
 }
 
@@ -214,11 +473,222 @@ impl Jenkins {
         J: Into<JobName<'a>>,
     {
         let _ = self
-            .post(&Path::AddJobToView {
+            .post(&Path::RemoveJobFromView {
                 job_name: Name::Name(job_name.into().0),
                 view_name: Name::Name(view_name.into().0),
             })
             .await?;
         Ok(())
     }
+
+    /// Create a view
+    pub async fn create_view(&self, name: &str, kind: ViewKind) -> Result<()> {
+        self.create_view_at(&Path::CreateView, name, kind).await
+    }
+
+    async fn create_view_at(&self, path: &Path<'_>, name: &str, kind: ViewKind) -> Result<()> {
+        let body = serde_urlencoded::to_string([
+            ("name", name),
+            ("mode", kind.class()),
+            (
+                "json",
+                &serde_json::json!({"name": name, "mode": kind.class()}).to_string(),
+            ),
+        ])?;
+        let _ = check_permission(self.post_with_body(path, body, &[]).await, "View/Create")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn can_set_include_regex() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/job/folder/view/my%20view/config.xml")
+            .with_body(
+                r#"<?xml version="1.1" encoding="UTF-8"?>
+<hudson.model.ListView>
+  <name>my view</name>
+  <includeRegex>old.*</includeRegex>
+  <recurse>false</recurse>
+</hudson.model.ListView>"#,
+            )
+            .create();
+        let update = s
+            .mock("POST", "/job/folder/view/my%20view/config.xml")
+            .match_header("content-type", "application/xml")
+            .match_body(
+                r#"<?xml version="1.1" encoding="UTF-8"?>
+<hudson.model.ListView>
+  <name>my view</name>
+  <recurse>false</recurse>
+  <includeRegex>job-&lt;\d+&gt;</includeRegex>
+</hudson.model.ListView>"#,
+            )
+            .create();
+
+        let view = ListView {
+            description: None,
+            name: "my view".to_string(),
+            url: format!("{}/job/folder/view/my%20view/", s.url()),
+            jobs: vec![],
+            property: vec![],
+        };
+        view.set_include_regex(&jenkins_client, Some(r"job-<\d+>"))
+            .await
+            .unwrap();
+
+        update.assert();
+    }
+
+    #[test]
+    fn can_read_sectioned_view() {
+        let view: CommonView = serde_json::from_str(
+            r#"{
+                "_class": "hudson.plugins.sectioned_view.SectionedView",
+                "description": null,
+                "name": "sections",
+                "url": "http://localhost:8080/view/sections/",
+                "jobs": [],
+                "property": [],
+                "sections": [
+                    {
+                        "_class": "hudson.plugins.sectioned_view.ListViewSection",
+                        "name": "builds",
+                        "jobNames": ["app", "lib"],
+                        "includeRegex": "build-.*"
+                    },
+                    {
+                        "_class": "hudson.plugins.sectioned_view.TextSection",
+                        "name": "notes"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let view = view.as_variant::<SectionedView>().unwrap();
+        assert_eq!(view.sections.len(), 2);
+        assert_eq!(view.sections[0].job_names, vec!["app", "lib"]);
+        assert_eq!(view.sections[0].include_regex.as_deref(), Some("build-.*"));
+        assert!(view.sections[1].job_names.is_empty());
+    }
+
+    #[test]
+    fn can_read_dashboard_view() {
+        let view: CommonView = serde_json::from_str(
+            r#"{
+                "_class": "hudson.plugins.view.dashboard.Dashboard",
+                "description": null,
+                "name": "dashboard",
+                "url": "http://localhost:8080/view/dashboard/",
+                "jobs": [],
+                "property": [],
+                "includeStdJobList": true,
+                "topPortlets": [],
+                "leftPortlets": [
+                    {
+                        "_class": "hudson.plugins.view.dashboard.stats.StatBuilds",
+                        "name": "Build statistics"
+                    }
+                ],
+                "rightPortlets": [],
+                "bottomPortlets": []
+            }"#,
+        )
+        .unwrap();
+
+        assert!(view.as_variant::<MyView>().is_err());
+        let view = view.as_variant::<DashboardView>().unwrap();
+        assert!(view.include_std_job_list);
+        assert!(view.top_portlets.is_empty());
+        assert_eq!(
+            view.left_portlets[0].name.as_deref(),
+            Some("Build statistics")
+        );
+    }
+
+    #[tokio::test]
+    async fn can_create_nested_view() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let mock = s
+            .mock("POST", "/view/parent/createView")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("name".into(), "child".into()),
+                mockito::Matcher::UrlEncoded("mode".into(), "hudson.model.ListView".into()),
+            ]))
+            .create();
+
+        let view = NestedView {
+            description: None,
+            name: "parent".to_string(),
+            url: format!("{}/view/parent/", s.url()),
+            jobs: vec![],
+            property: vec![],
+            views: vec![],
+        };
+        view.create_view(&jenkins_client, "child", ViewKind::ListView)
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn can_get_typed_full_view() {
+        let mut s = mockito::Server::new_async().await;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&s.url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _ = s
+            .mock("GET", "/view/parent/view/child/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                r#"{
+                    "_class": "hudson.plugins.nested_view.NestedView",
+                    "description": null,
+                    "name": "child",
+                    "url": "http://localhost:8080/view/parent/view/child/",
+                    "jobs": [],
+                    "property": [],
+                    "views": [
+                        {
+                            "_class": "hudson.model.ListView",
+                            "name": "leaf",
+                            "url": "http://localhost:8080/view/parent/view/child/view/leaf/"
+                        }
+                    ]
+                }"#,
+            )
+            .create();
+
+        let short_view: ShortView<NestedView> = serde_json::from_str(&format!(
+            r#"{{"name": "child", "url": "{}/view/parent/view/child/"}}"#,
+            s.url()
+        ))
+        .unwrap();
+        let view = short_view.get_full_view(&jenkins_client).await.unwrap();
+
+        assert_eq!(view.name, "child");
+        assert_eq!(view.views[0].name, "leaf");
+    }
 }
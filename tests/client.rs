@@ -12,6 +12,8 @@ use spectral::prelude::*;
 use jenkins_api::JenkinsBuilder;
 use jenkins_api::build::Build;
 use jenkins_api::job::{BuildableJob, Job, SCMPollable};
+use jenkins_api::nodes::computer::Computer;
+use jenkins_api::view::View;
 use std::{thread, time};
 
 use std::sync::Once;
@@ -120,8 +122,7 @@ async fn can_get_jenkins_view_from_home() {
     let first_view = home_ok
         .views
         .iter()
-        .filter(|view| view.name == "view disabled")
-        .next()
+        .find(|view| view.name == "view disabled")
         .unwrap();
     let full_view = first_view.get_full_view(&jenkins).await;
     assert!(full_view.is_ok());
@@ -449,7 +450,6 @@ async fn can_build_job_remotely() {
         .job_builder("remote job")
         .unwrap()
         .remotely_with_token_and_cause("remote_token", None)
-        .unwrap()
         .send()
         .await;
     let triggered_ok = triggered.unwrap();
@@ -558,7 +558,6 @@ async fn can_build_job_with_parameters() {
         .job_builder("parameterized job")
         .unwrap()
         .with_parameters(&params)
-        .unwrap()
         .send()
         .await;
     assert_that!(triggered).named("triggered job").is_ok();
@@ -600,6 +599,40 @@ async fn can_build_job_with_parameters() {
     assert!(found_param3);
 }
 
+#[tokio::test]
+async fn can_build_job_with_validated_parameters() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let triggered = jenkins
+        .job_builder("parameterized job")
+        .unwrap()
+        .with_build_parameters(
+            jenkins_api::job::builder::BuildParameters::new()
+                .with_bool("bool-param", true)
+                .with_choice("choose between", "value2")
+                .with_string("free string param", "my string param"),
+        )
+        .validate(true)
+        .send()
+        .await;
+    assert_that!(triggered).named("triggered job").is_ok();
+
+    let rejected = jenkins
+        .job_builder("parameterized job")
+        .unwrap()
+        .with_build_parameters(
+            jenkins_api::job::builder::BuildParameters::new().with_choice("choose between", "zut"),
+        )
+        .validate(true)
+        .send()
+        .await;
+    assert_that!(rejected).named("rejected job").is_err();
+}
+
 #[tokio::test]
 async fn can_poll_scm() {
     setup();
@@ -794,3 +827,394 @@ async fn can_get_by_path_with_depth() {
 
     assert!(r.is_ok());
 }
+
+#[tokio::test]
+async fn can_run_script() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    assert_that!(jenkins.run_script("println(1 + 1)").await.unwrap())
+        .is_equal_to("2\n".to_string());
+
+    let master = jenkins.get_master_node().await.unwrap();
+    assert_that!(master.run_script(&jenkins, "undefined_property").await).is_err();
+}
+
+#[tokio::test]
+async fn can_create_and_delete_agent() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let spec = jenkins_api::nodes::agent::AgentSpec::new("lifecycle agent", "/tmp/agent")
+        .with_labels(&["lifecycle"])
+        .with_executors(2);
+    assert_that!(jenkins.create_agent(&spec).await).is_ok();
+
+    let agent = jenkins.get_node("lifecycle agent").await.unwrap();
+    assert_that!(agent.num_executors).is_equal_to(2);
+
+    let config = agent.get_config_xml(&jenkins).await.unwrap();
+    assert_that!(config).contains("<label>lifecycle</label>");
+    assert_that!(
+        agent
+            .update_config_xml(&jenkins, &config.replace("lifecycle", "updated"))
+            .await
+    )
+    .is_ok();
+
+    assert_that!(agent.toggle_offline(&jenkins, Some("maintenance")).await).is_ok();
+    let agent = jenkins.get_node("lifecycle agent").await.unwrap();
+    assert_that!(agent.temporarily_offline).is_true();
+    assert_that!(
+        agent
+            .offline_cause
+            .as_ref()
+            .unwrap()
+            .as_variant::<jenkins_api::nodes::monitor::UserCause>()
+    )
+    .is_ok();
+    assert_that!(agent.health(&Default::default()).issues.contains(
+        &jenkins_api::nodes::health::HealthIssue::Offline {
+            reason: Some("maintenance".to_string())
+        }
+    ))
+    .is_true();
+    assert_that!(
+        agent
+            .assigned_labels
+            .iter()
+            .any(|label| label.name == "updated")
+    )
+    .is_true();
+
+    assert_that!(agent.delete(&jenkins).await).is_ok();
+    assert_that!(jenkins.get_node("lifecycle agent").await).is_err();
+}
+
+#[tokio::test]
+async fn can_get_agent_secret() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let spec = jenkins_api::nodes::agent::AgentSpec::new("inbound agent", "/tmp/inbound");
+    jenkins.create_agent(&spec).await.unwrap();
+
+    let agent: jenkins_api::nodes::computer::SlaveComputer = jenkins
+        .get_node("inbound agent")
+        .await
+        .unwrap()
+        .as_variant()
+        .unwrap();
+    let launch_info = agent.get_agent_secret(&jenkins).await;
+    agent.delete(&jenkins).await.unwrap();
+
+    let launch_info = launch_info.unwrap();
+    assert_that!(launch_info.agent_name).is_equal_to("inbound agent".to_string());
+    assert_that!(launch_info.command_line()).contains(launch_info.secret.expose());
+}
+
+#[tokio::test]
+async fn can_get_label_and_load_statistics() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let master = jenkins.get_master_node().await.unwrap();
+    let label = master.assigned_labels[0].get_full_label(&jenkins).await;
+    assert_that!(label).is_ok();
+    let label = label.unwrap();
+    assert_that!(label.nodes.len()).is_greater_than_or_equal_to(1);
+
+    let timescale = jenkins_api::nodes::load::Timescale::Minute;
+    assert_that!(label.get_load_statistics(&jenkins, timescale).await).is_ok();
+    assert_that!(master.get_load_statistics(&jenkins, timescale).await).is_ok();
+    assert_that!(jenkins.get_overall_load_statistics(timescale).await).is_ok();
+}
+
+#[tokio::test]
+async fn can_get_executors() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let executors = jenkins.get_executors().await;
+    assert_that!(executors).is_ok();
+    assert_that!(
+        executors
+            .unwrap()
+            .iter()
+            .any(|executor| executor.computer_name == "(master)")
+    )
+    .is_true();
+}
+
+#[tokio::test]
+async fn can_create_configure_and_delete_view() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    assert_that!(
+        jenkins
+            .create_view("created view", jenkins_api::view::ViewKind::ListView)
+            .await
+    )
+    .is_ok();
+
+    let view: jenkins_api::view::ListView = jenkins
+        .get_view("created view")
+        .await
+        .unwrap()
+        .as_variant()
+        .unwrap();
+    assert_that!(view.set_include_regex(&jenkins, Some("normal.*")).await).is_ok();
+    assert_that!(view.get_config_xml(&jenkins).await.unwrap())
+        .contains("<includeRegex>normal.*</includeRegex>");
+    let view = jenkins.get_view("created view").await.unwrap();
+    assert_that!(view.jobs.iter().any(|job| job.name == "normal job")).is_true();
+
+    assert_that!(view.delete(&jenkins).await).is_ok();
+    assert_that!(jenkins.get_view("created view").await).is_err();
+}
+
+#[tokio::test]
+async fn can_get_users() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let who_am_i = jenkins.who_am_i().await.unwrap();
+    assert_that!(who_am_i.name).is_equal_to("user".to_string());
+    assert_that!(who_am_i.anonymous).is_false();
+
+    let user = jenkins.get_user("user").await;
+    assert_that!(user).is_ok();
+
+    let people = jenkins.list_people().await;
+    assert_that!(people).is_ok();
+}
+
+#[tokio::test]
+async fn can_manage_api_tokens() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let token = jenkins
+        .generate_api_token("user", "integration test")
+        .await
+        .unwrap();
+    assert_that!(token.name).is_equal_to("integration test".to_string());
+
+    let tokens = jenkins.list_api_tokens("user").await.unwrap();
+    assert_that!(tokens.iter().any(|t| t.uuid == token.uuid)).is_true();
+
+    assert_that!(jenkins.revoke_api_token("user", &token.uuid).await).is_ok();
+    let tokens = jenkins.list_api_tokens("user").await.unwrap();
+    assert_that!(tokens.iter().any(|t| t.uuid == token.uuid)).is_false();
+}
+
+#[tokio::test]
+async fn can_manage_credentials() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let store = jenkins_api::credentials::CredentialsStore::System;
+    let credential = jenkins_api::credentials::Credential::new(
+        "integration-test",
+        jenkins_api::credentials::CredentialKind::UsernamePassword {
+            username: "deploy".to_string(),
+            password: "hunter2".into(),
+        },
+    );
+    assert_that!(jenkins.create_credential(store, &credential).await).is_ok();
+
+    let credential = credential.with_description("updated");
+    assert_that!(jenkins.update_credential(store, &credential).await).is_ok();
+
+    let credentials = jenkins.list_credentials(store).await.unwrap();
+    let listed = credentials
+        .iter()
+        .find(|c| c.id == "integration-test")
+        .unwrap();
+    assert_that!(listed.description).is_equal_to(Some("updated".to_string()));
+
+    assert_that!(jenkins.delete_credential(store, "integration-test").await).is_ok();
+    let credentials = jenkins.list_credentials(store).await.unwrap();
+    assert_that!(credentials.iter().any(|c| c.id == "integration-test")).is_false();
+}
+
+#[tokio::test]
+async fn can_get_plugins() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let plugins = jenkins.get_plugins().await.unwrap();
+    assert_that!(plugins.is_empty()).is_false();
+
+    let status = jenkins.update_center_status().await;
+    assert_that!(status).is_ok();
+    assert_that!(jenkins.get_plugins_requiring_restart().await).is_ok();
+}
+
+#[tokio::test]
+async fn can_quiet_down() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    assert_that!(jenkins.quiet_down(Some("maintenance")).await).is_ok();
+    assert_that!(jenkins.get_home().await.unwrap().quieting_down).is_true();
+
+    assert_that!(jenkins.cancel_quiet_down().await).is_ok();
+    assert_that!(jenkins.get_home().await.unwrap().quieting_down).is_false();
+
+    // Jenkins was not restarted, it never goes down
+    assert_that!(
+        jenkins
+            .wait_until_up(std::time::Duration::from_secs(3))
+            .await
+    )
+    .is_err();
+}
+
+#[tokio::test]
+async fn can_get_version_and_capabilities() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let version = jenkins.version().await.unwrap();
+    assert_that!(version.major).is_equal_to(2);
+
+    let capabilities = jenkins.capabilities().await.unwrap();
+    assert_that!(capabilities.version).is_equal_to(version);
+}
+
+#[tokio::test]
+async fn can_get_blue_ocean_pipelines() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    if jenkins.capabilities().await.unwrap().blue_ocean != Some(true) {
+        return;
+    }
+
+    let pipelines = jenkins
+        .get_blue_pipelines(jenkins_api::blueocean::Pagination::default())
+        .await;
+    assert_that!(pipelines).is_ok();
+}
+
+#[tokio::test]
+async fn can_get_pipeline_graph() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .with_depth(3)
+        .build()
+        .unwrap();
+
+    let build = jenkins.get_build("pipeline job", 1).await.unwrap();
+    let flow_graph = build
+        .actions
+        .iter()
+        .find_map(|action| {
+            action
+                .as_variant::<jenkins_api::action::FlowGraphAction>()
+                .ok()
+        })
+        .unwrap();
+
+    let graph = flow_graph.graph();
+    let root = graph.root().unwrap();
+    assert_that!(root.kind).is_equal_to(jenkins_api::action::pipeline::GraphNodeKind::Flow);
+    assert_that!(root.children.is_empty()).is_false();
+    assert_that!(root.duration()).is_some();
+    let dot = graph.to_dot();
+    for (index, node) in graph.nodes().iter().enumerate() {
+        if let Some(parent) = node.parent {
+            assert_that!(graph.nodes()[parent].children).contains(index);
+            assert_that!(dot).contains(&*format!(
+                "\"{}\" -> \"{}\";",
+                graph.nodes()[parent].id,
+                node.id
+            ));
+        }
+        assert_that!(node.start_time).is_some();
+        assert_that!(node.end_time).is_some();
+    }
+}
+
+#[tokio::test]
+async fn can_validate_declarative_pipeline() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let jenkinsfile = "pipeline {\n  agent any\n  stages {\n    stage('Build') {\n      steps {\n        echo 'hello'\n      }\n    }\n  }\n}\n";
+    let errors = jenkins.validate_declarative_pipeline(jenkinsfile).await;
+    assert_that!(errors.unwrap().is_empty()).is_true();
+
+    let errors = jenkins
+        .validate_declarative_pipeline("pipeline {\n  stages {\n  }\n}\n")
+        .await;
+    assert_that!(errors.unwrap().is_empty()).is_false();
+
+    let json = jenkins.convert_to_json(jenkinsfile).await.unwrap();
+    let converted = jenkins.convert_to_jenkinsfile(&json).await.unwrap();
+    assert_that!(converted).contains("echo 'hello'");
+}
+
+#[tokio::test]
+async fn can_export_and_check_casc() {
+    setup();
+    let jenkins = JenkinsBuilder::new(JENKINS_URL)
+        .with_user("user", Some("password"))
+        .build()
+        .unwrap();
+
+    let yaml = jenkins.export_casc().await.unwrap();
+    assert_that!(yaml).contains("jenkins:");
+
+    assert_that!(jenkins.check_casc(&yaml).await).is_ok();
+
+    let invalid = jenkins
+        .apply_casc("jenkins:\n  notAnAttribute: true\n")
+        .await;
+    assert_that!(invalid).is_err();
+}